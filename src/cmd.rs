@@ -1,5 +1,5 @@
-use crate::config::{Config, DirFile, API};
-use anyhow::{anyhow, Result};
+use crate::config::{Apis, Config, DirFile, API, APIS};
+use anyhow::{anyhow, Context, Result};
 use argh::FromArgs;
 use std::{
     env::var,
@@ -17,7 +17,15 @@ const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/VERSION"));
 例子：
 * `bilingual -a baidu multi queries -q single-query`
 * `bilingual -a tencent -m xx.md`
+* `bilingual -a baidu --dict -q apple`：查词典，输出译文、音标和释义
 * `bilingual -a niutrans -d ./dir-path`
+* `bilingual -a tencent,baidu,niutrans -d ./dir-path`：腾讯云翻译失败的段落依次交给百度翻译、小牛翻译
+* `bilingual -a alibaba -m xx.md`
+* `bilingual -a deepl -m xx.md -f en -t de`
+* `bilingual -a llm -m xx.md`：需要在配置文件的 [llm] 中设置 base_url 和 model
+* `bilingual -a libretranslate -m xx.md`：使用自部署的 LibreTranslate 实例，默认为 http://127.0.0.1:5000
+* `bilingual -a command -m xx.md`：调用配置文件的 [command] 中设置的外部程序来翻译
+* `bilingual -a pseudo -m xx.md`：离线的伪本地化，无需翻译 API 帐号，用于测试
 * `bilingual -a tencent \#\ 标题 正文：模拟\ markdown\ 文件的内容。 -f zh -t en`
 * `bilingual -a tencent -m xx.md -M xx-中文.md -d path -D path-中文`
 * `bilingual -a tencent -m a.md -M a.md -m b.md -M b.md -d assets -D assets -d test -D test -r`
@@ -27,9 +35,12 @@ const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/VERSION"));
       支持从环境变量或者配置文件 `bilingual.toml` 中获取信息，见 https://github.com/zjp-CN/bilingual/issues/27
 "#)]
 pub struct Bilingual {
-    /// 翻译 API。必选参数。目前支持：baidu | tencent | niutrans。
-    #[argh(option, short = 'a', default = "API::default()")]
-    api: API,
+    /// 翻译 API。必选参数。目前支持：baidu | tencent | niutrans | youdao | alibaba |
+    /// volcengine | deepl | llm | libretranslate | azure | google | caiyun | command |
+    /// pseudo。以逗号分隔多个 API 时，第一个为主 API，其余为备用 API，比如
+    /// `tencent,baidu`。
+    #[argh(option, short = 'a', default = "Apis::default()")]
+    api: Apis,
 
     /// 翻译 API 账户的 id。
     #[argh(option, short = 'i', default = "String::new()")]
@@ -39,7 +50,10 @@ pub struct Bilingual {
     #[argh(option, short = 'k', default = "String::new()")]
     key: String,
 
-    /// 原语言。默认为 en。
+    /// 原语言。默认为 en。可使用任一 API 的语言代码（比如 ja 或 jp、zh-TW 或 cht），
+    /// 除了 llm、command 和 pseudo，都会转换成对应 API 的代码，并在请求之前检查语言对。
+    /// 为 auto 时自动识别原语言：小牛翻译在本地识别，腾讯云调用 LanguageDetect
+    /// 接口，识别结果记录在日志中。
     #[argh(option, short = 'f', default = "String::from(\"en\")")]
     from: String,
 
@@ -51,6 +65,11 @@ pub struct Bilingual {
     #[argh(option, short = 'q', default = "String::new()")]
     singlequery: String,
 
+    /// 命令行输入为单词或者短语时查词典，在译文之后输出音标、词性和释义。目前只有百度翻译
+    /// 提供词典，其他 API 照常翻译。
+    #[argh(switch)]
+    dict: bool,
+
     /// md 文件的输入路径。此工具把读取到的文件内容只当作 md 文件进行处理。且不修改 API
     /// 返回的任何内容。可多次指定。
     #[argh(option, short = 'm', long = "input-files")]
@@ -114,11 +133,18 @@ impl Bilingual {
             std::process::exit(0);
         }
         let mut cf = Config::init(self.toml)?;
-        match self.api {
-            API::Baidu => baidu(self.id, self.key, &mut cf)?,
-            API::Tencent => tencent(self.id, self.key, &mut cf)?,
-            API::Niutrans => niutrans(self.key, &mut cf)?,
-            _ => anyhow::bail!("请输入 `-a` 参数来指定 baidu | tencent | niutrans 中的一个"),
+        let mut apis = self.api.0.into_iter();
+        let api = apis.next().unwrap_or_default();
+        credentials(api, self.id, self.key, &mut cf)?;
+        // 命令行指定的备用 API 覆盖配置文件中的 `fallback`
+        let fallback: Vec<_> = apis.collect();
+        if !fallback.is_empty() {
+            cf.fallback = fallback;
+        }
+        for api in cf.fallback.clone() {
+            // 备用 API 的帐号信息只来自配置文件或者环境变量
+            credentials(api, String::new(), String::new(), &mut cf)
+                .with_context(|| format!("请设置备用 API {api} 的帐号信息"))?;
         }
 
         if self.output_files.is_empty() {
@@ -144,15 +170,40 @@ impl Bilingual {
             self.multiquery.push(self.singlequery);
         }
         cf.src.query = self.multiquery.join("\n\n");
+        cf.src.dict = self.dict;
 
-        cf.api = self.api;
+        cf.api = api;
+        cf.init_translator()?;
         cf.src.from = self.from;
         cf.src.to = self.to;
+        cf.check_languages()?;
         cf.src.dir_file = DirFile::new(self.replace_file, self.forbid_dir_creation);
         Ok(cf)
     }
 }
 
+/// 把命令行参数或者环境变量中的 id 和 key 写入 `api` 对应的配置。
+fn credentials(api: API, id: String, key: String, cf: &mut Config) -> Result<()> {
+    match api {
+        API::Baidu => baidu(id, key, cf)?,
+        API::Tencent => tencent(id, key, cf)?,
+        API::Niutrans => niutrans(key, cf)?,
+        API::Youdao => youdao(id, key, cf)?,
+        API::Alibaba => alibaba(id, key, cf)?,
+        API::Volcengine => volcengine(id, key, cf)?,
+        API::DeepL => deepl(key, cf)?,
+        API::Llm => llm(key, cf),
+        API::LibreTranslate => libretranslate(key, cf),
+        API::Azure => azure(key, cf)?,
+        API::Google => google(key, cf)?,
+        API::Caiyun => caiyun(key, cf)?,
+        API::Command => debug!("外部命令无需 id 和 key"),
+        API::Pseudo => debug!("伪本地化无需 id 和 key"),
+        API::None => anyhow::bail!("请输入 `-a` 参数来指定 {APIS} 中的一个"),
+    }
+    Ok(())
+}
+
 fn new_filename(f: &Path, to: &str) -> PathBuf {
     let mut stem = f.file_stem().unwrap().to_os_string();
     stem.reserve(6);
@@ -210,7 +261,70 @@ fn niutrans(key: String, cf: &mut Config) -> Result<()> {
     }
 }
 
+fn azure(key: String, cf: &mut Config) -> Result<()> {
+    id_key! {
+        cf, azure, "Azure 翻译",
+        key = "BILINGUAL_AZURE_KEY"
+    }
+}
+
+fn deepl(key: String, cf: &mut Config) -> Result<()> {
+    id_key! {
+        cf, deepl, "DeepL",
+        key = "BILINGUAL_DEEPL_KEY"
+    }
+}
+
+fn caiyun(key: String, cf: &mut Config) -> Result<()> {
+    id_key! {
+        cf, caiyun, "彩云小译",
+        key = "BILINGUAL_CAIYUN_KEY"
+    }
+}
+
+fn google(key: String, cf: &mut Config) -> Result<()> {
+    id_key! {
+        cf, google, "Google 翻译",
+        key = "BILINGUAL_GOOGLE_KEY"
+    }
+}
+
+/// 本地服务可以不需要 api_key，所以 key 可以为空。
+fn llm(key: String, cf: &mut Config) {
+    if let Some(c) = cf.llm.as_mut() {
+        if !key.is_empty() {
+            c.api_key = key;
+            debug!("api_key 被命令行参数覆盖");
+        } else if let Ok(s) = var("BILINGUAL_LLM_KEY") {
+            c.api_key = s;
+            debug!("api_key 被 BILINGUAL_LLM_KEY 环境变量覆盖");
+        }
+    }
+}
+
+/// 实例可以不开启 API key 认证，所以 key 可以为空。
+fn libretranslate(key: String, cf: &mut Config) {
+    let c = cf.libretranslate.get_or_insert_with(Default::default);
+    if !key.is_empty() {
+        c.api_key = Some(key);
+        debug!("api_key 被命令行参数覆盖");
+    } else if let Ok(s) = var("BILINGUAL_LIBRETRANSLATE_KEY") {
+        c.api_key = Some(s);
+        debug!("api_key 被 BILINGUAL_LIBRETRANSLATE_KEY 环境变量覆盖");
+    }
+}
+
+/// 配置了 `refresh` 时，临时密钥在翻译之前读取，无需 id 和 key。
+/// 使用临时密钥时，token 可以由 BILINGUAL_TENCENT_TOKEN 环境变量提供。
 fn tencent(id: String, key: String, cf: &mut Config) -> Result<()> {
+    if cf.tencent.as_ref().is_some_and(|c| c.refresh.is_some()) {
+        debug!("腾讯云使用 refresh 读取临时密钥");
+        return Ok(());
+    }
+    if let Ok(s) = var("BILINGUAL_TENCENT_TOKEN") {
+        cf.tencent.get_or_insert_with(Default::default).token = Some(s);
+        debug!("token 被 BILINGUAL_TENCENT_TOKEN 环境变量覆盖");
+    }
     id_key! {
         cf, tencent, "腾讯云",
         key = "BILINGUAL_TENCENT_KEY",
@@ -218,6 +332,30 @@ fn tencent(id: String, key: String, cf: &mut Config) -> Result<()> {
     }
 }
 
+fn youdao(appkey: String, key: String, cf: &mut Config) -> Result<()> {
+    id_key! {
+        cf, youdao, "有道智云",
+        key = "BILINGUAL_YOUDAO_KEY",
+        appkey = "BILINGUAL_YOUDAO_ID"
+    }
+}
+
+fn alibaba(id: String, key: String, cf: &mut Config) -> Result<()> {
+    id_key! {
+        cf, alibaba, "阿里云",
+        key = "BILINGUAL_ALIBABA_KEY",
+        id = "BILINGUAL_ALIBABA_ID"
+    }
+}
+
+fn volcengine(id: String, key: String, cf: &mut Config) -> Result<()> {
+    id_key! {
+        cf, volcengine, "火山引擎",
+        key = "BILINGUAL_VOLCENGINE_KEY",
+        id = "BILINGUAL_VOLCENGINE_ID"
+    }
+}
+
 fn baidu(appid: String, key: String, cf: &mut Config) -> Result<()> {
     id_key! {
         cf, baidu, "百度翻译",
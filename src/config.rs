@@ -1,32 +1,71 @@
-use crate::md::Md;
+use crate::{
+    md::Md,
+    translator::{Command, Fallback, Pseudo, Sts, Translator, Usage},
+};
 use anyhow::{Context, Error, Result};
-use reqwest::blocking::{self, Client};
-use serde_json::from_slice;
 use std::path::{Path, PathBuf};
 use translation_api_cn::{
-    baidu::User as Baidu, niutrans::User as Niutrans, tencent::User as Tencent, Limit,
+    alibaba::User as Alibaba, azure::User as Azure, baidu::User as Baidu, caiyun::User as Caiyun,
+    deepl::User as DeepL, google::User as Google, libretranslate::User as LibreTranslate,
+    llm::User as Llm, niutrans::User as Niutrans, tencent::User as Tencent,
+    volcengine::User as Volcengine, youdao::User as Youdao,
 };
 
 #[derive(Debug, Default, serde::Deserialize)]
 pub struct Config {
     #[serde(skip_deserializing)]
-    pub src:      Src,
+    pub src:            Src,
     #[serde(skip_deserializing)]
-    pub api:      API,
-    pub baidu:    Option<Baidu>,
-    pub tencent:  Option<Tencent>,
-    pub niutrans: Option<Niutrans>,
+    pub api:            API,
+    /// 备用 API：主 API 翻译失败时依次使用，比如 `fallback = ["baidu", "niutrans"]`
+    #[serde(default)]
+    pub fallback:       Vec<API>,
+    /// 根据 [`API`] 构造的翻译接口，见 [`Config::init_translator`]
+    #[serde(skip_deserializing)]
+    pub translator:     Option<Box<dyn Translator>>,
+    /// 每份翻译内容由哪些 API 翻译，见 [`Config::log_report`]
+    #[serde(skip_deserializing)]
+    pub report:         Vec<Report>,
+    pub baidu:          Option<Baidu>,
+    pub tencent:        Option<Tencent>,
+    pub niutrans:       Option<Niutrans>,
+    pub youdao:         Option<Youdao>,
+    pub alibaba:        Option<Alibaba>,
+    pub volcengine:     Option<Volcengine>,
+    pub deepl:          Option<DeepL>,
+    pub llm:            Option<Llm>,
+    pub libretranslate: Option<LibreTranslate>,
+    pub azure:          Option<Azure>,
+    pub google:         Option<Google>,
+    pub caiyun:         Option<Caiyun>,
+    pub command:        Option<Command>,
+    pub pseudo:         Option<Pseudo>,
 }
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum API {
     None,
     Baidu,
     Tencent,
     Niutrans,
+    Youdao,
+    Alibaba,
+    Volcengine,
+    DeepL,
+    Llm,
+    LibreTranslate,
+    Azure,
+    Google,
+    Caiyun,
+    Command,
+    Pseudo,
 }
 
+/// 所支持的翻译 API
+pub const APIS: &str = "baidu | tencent | niutrans | youdao | alibaba | volcengine | deepl | llm \
+                        | libretranslate | azure | google | caiyun | command | pseudo";
+
 impl Default for API {
     fn default() -> Self { Self::None }
 }
@@ -39,11 +78,68 @@ impl std::str::FromStr for API {
             b"baidu" => Ok(API::Baidu),
             b"tencent" => Ok(API::Tencent),
             b"niutrans" => Ok(API::Niutrans),
-            _ => anyhow::bail!("请输入以下 API 之一: baidu | tencent | niutrans"),
+            b"youdao" => Ok(API::Youdao),
+            b"alibaba" => Ok(API::Alibaba),
+            b"volcengine" => Ok(API::Volcengine),
+            b"deepl" => Ok(API::DeepL),
+            b"llm" => Ok(API::Llm),
+            b"libretranslate" => Ok(API::LibreTranslate),
+            b"azure" => Ok(API::Azure),
+            b"google" => Ok(API::Google),
+            b"caiyun" => Ok(API::Caiyun),
+            b"command" => Ok(API::Command),
+            b"pseudo" => Ok(API::Pseudo),
+            _ => anyhow::bail!("请输入以下 API 之一: {APIS}"),
         }
     }
 }
 
+impl API {
+    pub fn name(self) -> &'static str {
+        match self {
+            API::None => "none",
+            API::Baidu => "baidu",
+            API::Tencent => "tencent",
+            API::Niutrans => "niutrans",
+            API::Youdao => "youdao",
+            API::Alibaba => "alibaba",
+            API::Volcengine => "volcengine",
+            API::DeepL => "deepl",
+            API::Llm => "llm",
+            API::LibreTranslate => "libretranslate",
+            API::Azure => "azure",
+            API::Google => "google",
+            API::Caiyun => "caiyun",
+            API::Command => "command",
+            API::Pseudo => "pseudo",
+        }
+    }
+}
+
+impl std::fmt::Display for API {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(self.name()) }
+}
+
+impl<'de> serde::Deserialize<'de> for API {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// `-a` 参数：以逗号分隔的 API，比如 `tencent,baidu,niutrans`。
+/// 第一个为主 API，其余为备用 API。
+#[derive(Debug, Default)]
+pub struct Apis(pub Vec<API>);
+
+impl std::str::FromStr for Apis {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',').map(|api| api.trim().parse()).collect::<Result<_>>().map(Apis)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Src {
     /// 原语言
@@ -52,6 +148,8 @@ pub struct Src {
     pub to:           String,
     /// 来自输入的命令行参数
     pub query:        String,
+    /// 命令行输入为单词或者短语时，是否查词典，见 [`Translator::lookup`]
+    pub dict:         bool,
     /// 如果输出文件已存在，是否替换。默认不替换。
     pub dir_file:     DirFile,
     /// 未校验 md 后缀的文件
@@ -196,27 +294,142 @@ impl Config {
         }
     }
 
+    /// 根据 [`API`] 和备用 API 取出对应的帐号信息，构造翻译接口。
+    pub fn init_translator(&mut self) -> Result<()> {
+        let primary = self.build_translator(self.api)?;
+        let mut fallback = std::mem::take(&mut self.fallback);
+        let mut seen = vec![self.api];
+        fallback.retain(|&api| {
+                    let new = !seen.contains(&api);
+                    if new {
+                        seen.push(api);
+                    }
+                    new
+                });
+        let mut chain = Vec::with_capacity(fallback.len() + 1);
+        for &api in &fallback {
+            let translator =
+                self.build_translator(api).with_context(|| format!("无法使用备用 API {api}"))?;
+            chain.push((api.name(), translator));
+        }
+        self.translator = Some(if chain.is_empty() {
+                                   primary
+                               } else {
+                                   chain.insert(0, (self.api.name(), primary));
+                                   Box::new(Fallback::new(chain))
+                               });
+        self.fallback = fallback;
+        Ok(())
+    }
+
+    /// 在发送任何请求之前，检查翻译接口是否支持 `-f/-t` 的语言对。
+    pub fn check_languages(&self) -> Result<()> {
+        match &self.translator {
+            Some(t) => t.check_languages(&self.src.from, &self.src.to),
+            None => Ok(()),
+        }
+    }
+
+    /// 根据 [`API`] 取出对应的帐号信息，构造一个翻译接口。
+    fn build_translator(&mut self, api: API) -> Result<Box<dyn Translator>> {
+        Ok(match api {
+            API::Baidu => {
+                let baidu = self.baidu.take().context("请设置百度翻译 API 帐号的 id 和 key")?;
+                anyhow::ensure!(baidu.domain.is_none() || !baidu.glossary.custom,
+                                "百度翻译的领域翻译不支持自定义术语库：domain 与 glossary.custom \
+                                 不能同时设置");
+                Box::new(baidu)
+            }
+            API::Tencent => {
+                let mut tencent =
+                    self.tencent.take().context("请设置腾讯云 API 帐号的 id 和 key")?;
+                match tencent.refresh.take() {
+                    Some(refresh) => Box::new(Sts::new(tencent, refresh)),
+                    None => Box::new(tencent),
+                }
+            }
+            API::Niutrans => {
+                Box::new(self.niutrans.take().context("请设置小牛翻译 API 帐号的 key")?)
+            }
+            API::Youdao => {
+                Box::new(self.youdao.take().context("请设置有道智云 API 帐号的 id 和 key")?)
+            }
+            API::Alibaba => {
+                Box::new(self.alibaba.take().context("请设置阿里云 API 帐号的 id 和 key")?)
+            }
+            API::Volcengine => {
+                Box::new(self.volcengine.take().context("请设置火山引擎 API 帐号的 id 和 key")?)
+            }
+            API::DeepL => Box::new(self.deepl.take().context("请设置 DeepL API 帐号的 key")?),
+            API::Llm => Box::new(self.llm.take().context("请在配置文件的 [llm] 中设置 model")?),
+            API::LibreTranslate => Box::new(self.libretranslate.take().unwrap_or_default()),
+            API::Azure => Box::new(self.azure.take().context("请设置 Azure 翻译 API 的 key")?),
+            API::Google => Box::new(self.google.take().context("请设置 Google 翻译 API 的 key")?),
+            API::Caiyun => Box::new(self.caiyun.take().context("请设置彩云小译 API 的 token")?),
+            API::Command => {
+                Box::new(self.command.take().context("请在配置文件的 [command] 中设置 program")?)
+            }
+            API::Pseudo => Box::new(self.pseudo.take().unwrap_or_default()),
+            API::None => anyhow::bail!("请输入以下 API 之一: {APIS}"),
+        })
+    }
+
     /// 按照 [`files`][`Src::file`] -> [`dirs`][`Src::dirs`] -> [`query`][`Src::query`] 的
     /// 顺序查询。
     pub fn do_single_query(&mut self) -> Option<TextItem> {
         use TextItem::*;
         let text_item = self.src.next()?;
-        let doit = |text: &str| {
+        let dict = self.src.dict;
+        let mut doit = |text: &str, name: String, dict: bool| {
             let md = Md::new(text);
-            match self.api {
-                API::Baidu => self.do_single_query_baidu(md),
-                API::Tencent => self.do_single_query_tencent(md),
-                API::Niutrans => self.do_single_query_niutrans(md),
-                _ => unimplemented!(),
+            let translator = self.translator.as_ref().or_else(|| {
+                                                          error!("未指定翻译 API");
+                                                          None
+                                                      })?;
+            let detected = detect(&**translator, text, &self.src.from).map_err(print_err).ok()?;
+            if let Some(lang) = &detected {
+                info!("{name}：原语言识别为 {lang}");
+            }
+            let from = detected.as_deref().unwrap_or(&self.src.from);
+            if dict && is_term(text) {
+                match translator.lookup(text.trim(), from, &self.src.to) {
+                    Ok(Some(entry)) => {
+                        self.report
+                            .push(Report { name, api: self.api, detected, usage: Vec::new() });
+                        return Some(entry);
+                    }
+                    Ok(None) => (),
+                    Err(err) => warn!("查词失败，改为翻译：\n{err}"),
+                }
             }
+            let output = translator.translate_md(md, from, &self.src.to);
+            let usage = translator.take_usage();
+            let output = output.map_err(print_err).ok()?;
+            self.report.push(Report { name, api: self.api, detected, usage });
+            Some(output)
         };
         Some(match text_item {
-            Normal { ref text, from, into } => Normal { text: doit(text)?, from, into },
-            Stdout(ref s) => Stdout(doit(s)?),
+            Normal { ref text, from, into } => {
+                Normal { text: doit(text, from.display().to_string(), false)?,
+                         from,
+                         into }
+            }
+            Stdout(ref s) => Stdout(doit(s, "命令行输入".into(), dict)?),
             x => x,
         })
     }
 
+    /// 记录每份翻译内容由哪些 API 翻译。使用了备用 API 时以 warn 级别记录。
+    pub fn log_report(&self) {
+        for report in &self.report {
+            if report.usage.iter().any(|u| u.api != self.api.name()) {
+                warn!("{report}");
+            } else {
+                info!("{report}");
+            }
+        }
+    }
+
     pub fn do_single_query_write(&mut self) -> Option<String> {
         match self.do_single_query()? {
             TextItem::Normal { text, from, into } => {
@@ -237,213 +450,64 @@ impl Config {
             }
         }
     }
-
-    pub fn do_single_query_baidu(&self, md: Md) -> Option<String> {
-        self.baidu
-            .as_ref()
-            .or_else(|| {
-                error!("请设置百度翻译 API 帐号的 id 和 key");
-                None
-            })
-            .and_then(|b| {
-                if b.limit.limit() == 0 {
-                    via_baidu(md, &self.src.from, &self.src.to, b)
-                } else {
-                    via_baidu_batch(md, &self.src.from, &self.src.to, b)
-                }.map_err(|e| {
-                     print_err(e);
-                 })
-                 .ok()
-            })
-    }
-
-    pub fn do_single_query_tencent(&self, md: Md) -> Option<String> {
-        self.tencent
-            .as_ref()
-            .or_else(|| {
-                error!("请设置腾讯云 API 帐号的 id 和 key");
-                None
-            })
-            .and_then(|t| {
-                if t.limit.limit() == 0 {
-                    via_tencent(md, &self.src.from, &self.src.to, t)
-                } else {
-                    via_tencent_batch(md, &self.src.from, &self.src.to, t)
-                }.map_err(|e| {
-                     print_err(e);
-                 })
-                 .ok()
-            })
-    }
-
-    pub fn do_single_query_niutrans(&self, md: Md) -> Option<String> {
-        self.niutrans
-            .as_ref()
-            .or_else(|| {
-                error!("请设置小牛翻译 API 帐号的 key");
-                None
-            })
-            .and_then(|n| {
-                if n.limit.limit() == 0 {
-                    via_niutrans(md, &self.src.from, &self.src.to, n)
-                } else {
-                    via_niutrans_batch(md, &self.src.from, &self.src.to, n)
-                }.map_err(|e| {
-                     print_err(e);
-                 })
-                 .ok()
-            })
-    }
-}
-
-fn print_err<E: Into<Error> + std::fmt::Display>(e: E) { error!("{}", e) }
-
-/// 以 post + 表单方式发送
-fn send<T: serde::Serialize + ?Sized>(url: &str, form: &T) -> Result<blocking::Response> {
-    let response = Client::new().post(url).form(form).send()?;
-    debug_assert!(response.error_for_status_ref().is_ok());
-    Ok(response)
 }
 
-trait Batch {
-    fn limit_field(&self) -> &Limit;
-    fn limit<'t>(&self, m: &'t mut Md) -> Box<dyn Iterator<Item = &'t str> + 't> {
-        match *self.limit_field() {
-            Limit::Byte(l) => Box::new(m.bytes_paragraph(l)),
-            Limit::Char(l) => Box::new(m.chars_paragraph(l)),
-        }
+/// 原语言为 auto 时，取文本开头的一部分作为样本，由翻译接口识别原语言。
+/// 返回 None 表示无需识别或者由 API 自己识别。
+fn detect(translator: &dyn Translator, text: &str, from: &str) -> Result<Option<String>> {
+    /// 样本的字符数上限：腾讯云的 LanguageDetect 要求文本低于 2000 字符
+    const SAMPLE: usize = 1000;
+    if !from.eq_ignore_ascii_case("auto") {
+        return Ok(None);
     }
-}
-
-macro_rules! batch {
-    ($($i:ident),+) => {
-        $(impl Batch for $i { fn limit_field(&self) -> &Limit { &self.limit } })+
-    };
-}
-
-batch!(Baidu, Tencent, Niutrans);
-
-pub fn via_baidu_batch(mut md: Md, from: &str, to: &str, user: &Baidu) -> Result<String> {
-    use translation_api_cn::baidu::{Query, Response, URL};
-    let mut res = Vec::new();
-    let f = |q: &str| {
-        let mut query = Query::new(q.trim(), from, to);
-        let bytes = send(URL, &{
-                        let sign = query.sign(user);
-                        debug!("sign = {:#?}", sign);
-                        sign
-                    })?.bytes()?;
-        debug!("\nq = {:?}\nquery = {:#?}\nbytes = {:?}", q, query, bytes);
-        res.push(bytes);
-        Ok::<(), Error>(())
+    let mut md = Md::new(text);
+    let sample = md.extract();
+    let sample = match sample.char_indices().nth(SAMPLE) {
+        Some((i, _)) => &sample[..i],
+        None => sample,
     };
-    user.limit(&mut md).try_for_each(f)?;
-    let iter: Vec<Response> = res.iter().map(|bytes| from_slice(bytes)).collect::<Result<_, _>>()?;
-    debug!("iter = {:#?}", iter);
-    let output = md.done(iter.iter().flat_map(|r| r.dst().map_err(print_err).unwrap()));
-    Ok(output)
+    translator.detect(sample)
 }
 
-pub fn via_baidu(mut md: Md, from: &str, to: &str, user: &Baidu) -> Result<String> {
-    use translation_api_cn::baidu::{Query, Response, URL};
-    let q = md.extract();
-    let mut query = Query::new(q.trim(), from, to);
-    let bytes = send(URL, &{
-                    let sign = query.sign(user);
-                    debug!("sign = {:#?}", sign);
-                    sign
-                })?.bytes()?;
-    let response = from_slice::<Response>(&bytes)?;
-    debug!("\nq = {:?}\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", q, query, bytes, response);
-    let output = md.done(response.dst()?);
-    Ok(output)
+/// 是否为单词或者短语：单行、不超过三个词且不超过 32 个字符。
+fn is_term(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty()
+    && !text.contains('\n')
+    && text.split_whitespace().count() <= 3
+    && text.chars().count() <= 32
 }
 
-pub fn via_niutrans_batch(mut md: Md, from: &str, to: &str, user: &Niutrans) -> Result<String> {
-    use translation_api_cn::niutrans::{Query, Response, URL};
-    let mut res = Vec::new();
-    let f = |q: &str| {
-        let query = Query::new(q.trim(), from, to);
-        let bytes = send(URL, &{
-                        let form = query.form(user);
-                        debug!("form = {:#?}", form);
-                        form
-                    })?.bytes()?;
-        debug!("\nq = {:?}\nquery = {:#?}\nbytes = {:?}", q, query, bytes);
-        res.push(bytes);
-        Ok::<(), Error>(())
-    };
-    user.limit(&mut md).try_for_each(f)?;
-    let iter: Vec<Response> = res.iter().map(|bytes| from_slice(bytes)).collect::<Result<_, _>>()?;
-    debug!("iter = {:#?}", iter);
-    let output = md.done(iter.iter().flat_map(|r| r.dst().map_err(print_err).unwrap()));
-    Ok(output)
-}
-
-pub fn via_niutrans(mut md: Md, from: &str, to: &str, user: &Niutrans) -> Result<String> {
-    use translation_api_cn::niutrans::{Query, Response, URL};
-    let q = md.extract();
-    let query = Query::new(q.trim(), from, to);
-    let bytes = send(URL, &{
-                    let form = query.form(user);
-                    debug!("form = {:#?}", form);
-                    form
-                })?.bytes()?;
-    let response = from_slice::<Response>(&bytes)?;
-    debug!("\nq = {:?}\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", q, query, bytes, response);
-    let output = md.done(response.dst()?);
-    Ok(output)
-}
-
-#[rustfmt::skip]
-fn send2(header: &mut translation_api_cn::tencent::Header) -> Result<blocking::Response> {
-    header.authorization()?; // 更改 query 或者 user 时必须重新生成验证信息
-    let map = {
-        use reqwest::header::{HeaderName, HeaderValue};
-        use std::str::FromStr;
-        header.header()
-              .into_iter()
-              .filter_map(|(k, v)| match (HeaderName::from_str(k), HeaderValue::from_str(v)) {
-                  (Ok(key), Ok(value)) => Some((key, value)),
-                  _ => None,
-              }) // 遇到 Err 时，把 Ok 的部分 collect
-              .collect()
-    };
-    Client::new().post(translation_api_cn::tencent::URL).headers(map).json(header.query).send().map_err(|e| e.into())
+/// 一份翻译内容（文件或者命令行输入）的翻译情况
+#[derive(Debug)]
+pub struct Report {
+    /// 文件路径或者“命令行输入”
+    pub name:     String,
+    /// 主 API
+    pub api:      API,
+    /// `-f auto` 时在翻译之前识别出的原语言；由 API 自己识别时为 None
+    pub detected: Option<String>,
+    /// 配置了备用 API 时，各段落由哪个 API 翻译；否则为空，表示全部由主 API 翻译。
+    pub usage:    Vec<Usage>,
 }
 
-pub fn via_tencent_batch(mut md: Md, from: &str, to: &str, user: &Tencent) -> Result<String> {
-    use translation_api_cn::tencent::{Header, Query, Response};
-    // debug!("raw md = {:#?}", md.extract());
-    let mut res = Vec::new();
-    let f = |buf: &str| {
-        let q: Vec<&str> = buf.trim().split('\n').collect();
-        debug!("\nq = {:?}", q);
-        let query = Query::new(&q, from, to, user.projectid);
-        let mut header = Header::new(user, &query);
-        let bytes = send2(&mut header)?.bytes()?;
-        debug!("\nq = {:?}\nquery = {:#?}\nheader = {:#?}\nbytes = {:#?}", q, query, header, bytes);
-        res.push(bytes);
-        Ok::<(), Error>(())
-    };
-    user.limit(&mut md).try_for_each(f)?;
-    let iter: Vec<Response> = res.iter().map(|bytes| from_slice(bytes)).collect::<Result<_, _>>()?;
-    debug!("iter = {:#?}", iter);
-    let output = md.done(iter.iter().flat_map(|r| r.dst().map_err(print_err).unwrap()));
-    Ok(output)
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}：", self.name)?;
+        if let Some(lang) = &self.detected {
+            write!(f, "原语言识别为 {lang}，")?;
+        }
+        if self.usage.is_empty() {
+            return write!(f, "全部由 {} 翻译", self.api);
+        }
+        for (i, usage) in self.usage.iter().enumerate() {
+            if i != 0 {
+                f.write_str("，")?;
+            }
+            write!(f, "{usage}")?;
+        }
+        Ok(())
+    }
 }
 
-pub fn via_tencent(mut md: Md, from: &str, to: &str, user: &Tencent) -> Result<String> {
-    use translation_api_cn::tencent::{Header, Query, Response};
-
-    let buf = md.extract();
-    let q: Vec<&str> = buf.trim().split('\n').collect();
-    let query = Query::new(&q, from, to, user.projectid);
-    let mut header = Header::new(user, &query);
-    let bytes = send2(&mut header)?.bytes()?;
-    let response = from_slice::<Response>(&bytes)?;
-    debug!("\nq = {:?}\nquery = {:#?}\nheader = {:#?}\nbytes = {:?}\nresponse = {:#?}",
-           q, query, header, bytes, response);
-    let output = md.done(response.dst()?);
-    Ok(output)
-}
+fn print_err<E: Into<Error> + std::fmt::Display>(e: E) { error!("{}", e) }
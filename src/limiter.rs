@@ -0,0 +1,109 @@
+//! 令牌桶限流：按照帐号的 QPS 控制请求的间隔。
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// 时钟：获取当前时刻，以及等待一段时间。测试时可替换成不真正等待的时钟。
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, dur: Duration);
+}
+
+impl<C: Clock> Clock for &C {
+    fn now(&self) -> Instant { (*self).now() }
+
+    fn sleep(&self, dur: Duration) { (*self).sleep(dur) }
+}
+
+/// 系统时钟
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant { Instant::now() }
+
+    fn sleep(&self, dur: Duration) { std::thread::sleep(dur) }
+}
+
+/// 单个帐号的令牌桶
+#[derive(Debug)]
+struct Bucket {
+    /// 剩余令牌。为负数时，表示已被预约、尚未恢复的令牌。
+    tokens: f64,
+    /// 上次计算令牌的时刻
+    last:   Instant,
+}
+
+/// 以帐号为键的令牌桶限流器：同一帐号的所有请求共享一个令牌桶。
+///
+/// 令牌桶的容量和每秒恢复的令牌数都为 QPS：即最多连续发送 QPS 个请求，
+/// 之后每个请求间隔 `1 / QPS` 秒。
+#[derive(Debug)]
+pub struct RateLimiter<C = SystemClock> {
+    clock:   C,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl<C: Clock> RateLimiter<C> {
+    pub fn with_clock(clock: C) -> Self { Self { clock, buckets: Mutex::default() } }
+
+    /// 获取一个令牌：令牌不足时，等待至令牌恢复再返回。`qps` 为 0 时不限流。
+    pub fn acquire(&self, account: &str, qps: u8) {
+        if qps == 0 {
+            return;
+        }
+        let wait = {
+            let rate = f64::from(qps);
+            let now = self.clock.now();
+            let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+            let bucket =
+                buckets.entry(account.into()).or_insert(Bucket { tokens: rate, last: now });
+            let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(rate) - 1.0;
+            bucket.last = now;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        };
+        debug!("触发限流（QPS = {qps}），等待 {wait:?}");
+        self.clock.sleep(wait);
+    }
+}
+
+/// 所有翻译请求共用的限流器
+pub fn limiter() -> &'static RateLimiter {
+    static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+    LIMITER.get_or_init(|| RateLimiter::with_clock(SystemClock))
+}
+
+/// 不真正等待的时钟：等待时只推进时刻，并记录等待的时长
+#[cfg(test)]
+pub struct MockClock {
+    start:     Instant,
+    pub slept: Mutex<Vec<Duration>>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn new() -> Self { Self { start: Instant::now(), slept: Mutex::default() } }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant { self.start + self.slept.lock().unwrap().iter().sum::<Duration>() }
+
+    fn sleep(&self, dur: Duration) { self.slept.lock().unwrap().push(dur) }
+}
+
+#[test]
+fn rate_limiter() {
+    let clock = MockClock::new();
+    let limiter = RateLimiter::with_clock(&clock);
+    (0..4).for_each(|_| limiter.acquire("baidu:a", 2));
+    limiter.acquire("baidu:b", 2); // 不同帐号之间互不影响
+    (0..3).for_each(|_| limiter.acquire("niutrans:c", 0)); // 不限流
+    assert_eq!(*clock.slept.lock().unwrap(), [Duration::from_millis(500); 2]);
+}
@@ -5,6 +5,9 @@ extern crate log;
 
 mod cmd;
 mod config;
+mod limiter;
+mod retry;
+mod translator;
 
 #[cfg(test)]
 mod tests;
@@ -17,6 +20,7 @@ fn main() -> Result<()> {
     while let Some(output) = config.do_single_query_write() {
         log::trace!("{:?}", output);
     }
+    config.log_report();
 
     Ok(())
 }
@@ -0,0 +1,79 @@
+//! 请求失败时，按照 [`Retry`] 策略以指数退避的方式重试。
+use crate::limiter::Clock;
+use anyhow::{Error, Result};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+use translation_api_cn::Retry;
+
+/// 执行 `f`：遇到 `retriable` 判定为暂时性的错误时，等待一段时间后重试，
+/// 直至成功、遇到不可重试的错误或者达到最多请求次数。
+pub fn retry<T>(policy: &Retry, clock: &impl Clock, retriable: impl Fn(&Error) -> bool,
+                mut f: impl FnMut() -> Result<T>)
+                -> Result<T> {
+    let mut n = 1;
+    loop {
+        match f() {
+            Err(err) if n < policy.attempts && retriable(&err) => {
+                let wait = jitter(policy, policy.backoff(n));
+                warn!("第 {n} 次请求失败，{wait:?} 后重试：\n{err}");
+                clock.sleep(wait);
+                n += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// 在 `[wait/2, wait]` 之间随机选取等待时间，避免多个请求同时重试。
+fn jitter(policy: &Retry, wait: Duration) -> Duration {
+    if policy.jitter {
+        // 标准库的 RandomState 每次以随机的密钥初始化，足以作为抖动的随机来源
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        wait.mul_f64(0.5 + random * 0.5)
+    } else {
+        wait
+    }
+}
+
+/// 网络连接失败或者超时，这类错误可以重试。
+pub fn is_network_error(err: &Error) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+       .is_some_and(|e| e.is_timeout() || e.is_connect())
+}
+
+#[test]
+fn retry_on_transient_error() {
+    use crate::limiter::MockClock;
+    use translation_api_cn::baidu::Error;
+    let clock = MockClock::new();
+    let policy = Retry { attempts:    4,
+                         backoff:     100,
+                         max_backoff: 300,
+                         jitter:      false, };
+    let retriable = |e: &anyhow::Error| e.downcast_ref::<Error>().is_some_and(Error::is_retriable);
+    let error = |code: &str| Error { code: code.into(), msg: String::new() };
+
+    // 访问频率受限：重试 3 次之后成功
+    let mut codes = vec!["54003", "52001", "54005"].into_iter();
+    let res = retry(&policy, &clock, retriable, || match codes.next() {
+        Some(code) => Err(error(code).into()),
+        None => Ok("done"),
+    });
+    assert_eq!(res.unwrap(), "done");
+    let ms = Duration::from_millis;
+    assert_eq!(*clock.slept.lock().unwrap(), [ms(100), ms(200), ms(300)]);
+
+    // 签名错误：不重试
+    clock.slept.lock().unwrap().clear();
+    let mut cnt = 0;
+    let res = retry(&policy, &clock, retriable, || -> Result<()> {
+        cnt += 1;
+        Err(error("54001").into())
+    });
+    assert!(res.is_err());
+    assert_eq!(cnt, 1);
+    assert!(clock.slept.lock().unwrap().is_empty());
+}
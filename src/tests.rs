@@ -6,62 +6,197 @@ use insta::{assert_debug_snapshot, assert_display_snapshot};
 #[test]
 fn size() {
     use std::mem::size_of;
-    assert_debug_snapshot!(size_of::<Config>(), @"456");
+    assert_debug_snapshot!(size_of::<Config>(), @"2336");
     assert_debug_snapshot!(size_of::<Src>(), @"184");
-    assert_debug_snapshot!(size_of::<translation_api_cn::baidu::User>(), @"96");
+    assert_debug_snapshot!(size_of::<translation_api_cn::baidu::User>(), @"248");
 }
 
-// 需要指定 api，但是这个测试仅仅用作例子。
+// 百度翻译的替身服务按段落返回译文：检查 Markdown 的段落提取与译文的插入位置
 #[test]
-#[should_panic]
 fn translation() {
-    if let Ok(mut cf) = Config::init("bilingual.toml") {
-        cf.src.from = "en".into();
-        cf.src.to = "zh".into();
-        cf.src.query = MD.into();
-
-        if let Some(md) = cf.do_single_query() {
-            assert_display_snapshot!(md, @r###"
-            # I/O event queue
-
-            I/O事件队列
-
-            We add the `callback_id` to the collection of callbacks to run. We pass
-            in `Js::Undefined` since we'll not actually pass any data along here. You'll see
-            why when we reach the [Http module](./8_3_http_module.md) chapter, but the main
-            point is that the I/O queue doesn't return any data itself, it just tells us that
-            data is ready to be read.
-
-            我们将'callback_id'添加到要运行的回调集合中。我们传入'Js:：Undefined'，因为我们实际上不会在这里传递任何数据。当我们谈到Http模块一章时，您将看到为什么，但主要的一点是I/O队列本身不返回任何数据，它只是告诉我们数据已经准备好读取。
-
-            ```rust, ignored
-            fn process_epoll_events(&mut self, event_id: usize) {
-                self.callbacks_to_run.push((event_id, Js::Undefined));
-                self.epoll_pending_events -= 1;
-            }
-            ```
-
-            Hi!
-
-            你好
-
-             > 
-             > Hi!
-             > **Why even keep track of how many `epoll_events` are pending?**
-             > We don't use this value here, but I added it to make it easier to create
-             > some `print` statements showing the status of our runtime at different points.
-             > However, there are good reasons to keep track of these events even if we don't use them.
-             > 
-             > 你好为什么还要记录有多少“epoll_事件”悬而未决？我们这里不使用这个值，但我添加它是为了更容易地创建一些“print”语句来显示运行时在不同点的状态。然而，即使我们不使用它们，也有很好的理由跟踪这些事件。
-             > 
-             > One area we're taking shortcuts on all the way here is security. If someone were
-             > to build a public facing server out of this, we need to account for slow networks
-             > and malicious users.
-             > 
-             > 我们一路走捷径的一个领域是安全。如果有人要用它来构建一个面向公众的服务器，我们需要考虑慢速网络和恶意用户。
-            "###);
-        }
+    use crate::translator::tests::StandIn;
+    let dst = ["I/O事件队列",
+               "我们将'callback_id'添加到要运行的回调集合中。我们传入'Js:：Undefined'，\
+                因为我们实际上不会在这里传递任何数据。当我们谈到Http模块一章时，您将看到为什么，\
+                但主要的一点是I/O队列本身不返回任何数据，它只是告诉我们数据已经准备好读取。",
+               "你好",
+               "你好为什么还要记录有多少“epoll_事件”悬而未决？我们这里不使用这个值，\
+                但我添加它是为了更容易地创建一些“print”语句来显示运行时在不同点的状态。然而，\
+                即使我们不使用它们，也有很好的理由跟踪这些事件。",
+               "我们一路走捷径的一个领域是安全。如果有人要用它来构建一个面向公众的服务器，\
+                我们需要考虑慢速网络和恶意用户。"];
+    let trans_result: Vec<_> =
+        dst.iter().map(|dst| serde_json::json!({ "src": "", "dst": dst })).collect();
+    let reply = serde_json::json!({ "from": "en", "to": "zh", "trans_result": trans_result });
+    let stand_in = StandIn::new(vec![reply.to_string()]);
+    let toml = format!("[baidu]\nappid = \"id\"\nkey = \"key\"\nendpoint = \"{}\"", stand_in.url);
+    let mut cf = config(&toml, API::Baidu);
+    cf.src.query = MD.into();
+
+    assert_display_snapshot!(cf.do_single_query().unwrap(), @r###"
+    # I/O event queue
+
+    # I/O事件队列
+
+    We add the `callback_id` to the collection of callbacks to run. We pass
+    in `Js::Undefined` since we'll not actually pass any data along here. You'll see
+    why when we reach the [Http module](./8_3_http_module.md) chapter, but the main
+    point is that the I/O queue doesn't return any data itself, it just tells us that
+    data is ready to be read.
+
+    我们将'callback_id'添加到要运行的回调集合中。我们传入'Js:：Undefined'，因为我们实际上不会在这里传递任何数据。当我们谈到Http模块一章时，您将看到为什么，但主要的一点是I/O队列本身不返回任何数据，它只是告诉我们数据已经准备好读取。
+
+    ```rust, ignored
+    fn process_epoll_events(&mut self, event_id: usize) {
+        self.callbacks_to_run.push((event_id, Js::Undefined));
+        self.epoll_pending_events -= 1;
     }
+    ```
+
+    Hi!
+
+    你好
+
+     > 
+     > Hi!
+     > **Why even keep track of how many `epoll_events` are pending?**
+     > We don't use this value here, but I added it to make it easier to create
+     > some `print` statements showing the status of our runtime at different points.
+     > However, there are good reasons to keep track of these events even if we don't use them.
+     > 
+     > 你好为什么还要记录有多少“epoll_事件”悬而未决？我们这里不使用这个值，但我添加它是为了更容易地创建一些“print”语句来显示运行时在不同点的状态。然而，即使我们不使用它们，也有很好的理由跟踪这些事件。
+     > 
+     > One area we're taking shortcuts on all the way here is security. If someone were
+     > to build a public facing server out of this, we need to account for slow networks
+     > and malicious users.
+     > 
+     > 我们一路走捷径的一个领域是安全。如果有人要用它来构建一个面向公众的服务器，我们需要考虑慢速网络和恶意用户。
+    "###);
+    assert_eq!(stand_in.recv().form("q")[0].lines().count(), dst.len());
+}
+
+// 备用 API 去掉与主 API 以及彼此重复的项
+#[test]
+fn fallback_config() {
+    let toml = "fallback = [\"pseudo\", \"command\", \"pseudo\"]\n[command]\nprogram = \"sh\"";
+    let cf = config(toml, API::Command);
+    assert_eq!(cf.fallback, [API::Pseudo]);
+
+    let apis: crate::config::Apis = "tencent, baidu".parse().unwrap();
+    assert_eq!(apis.0, [API::Tencent, API::Baidu]);
+    assert!("tencent,xx".parse::<crate::config::Apis>().is_err());
+}
+
+#[test]
+fn language_pair_check() {
+    let toml = "fallback = [\"baidu\"]\n[tencent]\nid = \"id\"\nkey = \"key\"\n[baidu]\nappid = \
+                \"id\"\nkey = \"key\"";
+    let mut cf = config(toml, API::Tencent);
+    let check = |cf: &mut Config, from: &str, to: &str| {
+        cf.src.from = from.into();
+        cf.src.to = to.into();
+        cf.check_languages().map_err(|e| format!("{e:#}"))
+    };
+    assert_eq!(check(&mut cf, "en", "jp"), Ok(()));
+    assert_eq!(check(&mut cf, "ja", "ar"),
+               Err("tencent 无法翻译: 腾讯云不支持从 `ja` 翻译到 `ar`：`ja` 只能翻译成 \
+                    zh、zh-TW、en、ko"
+                                      .into()));
+    assert_eq!(check(&mut cf, "en", "hi"), Err("baidu 无法翻译: 百度翻译不支持语言 `hi`".into()));
+    assert_eq!(check(&mut cf, "en", "zn"), Err("tencent 无法翻译: 无法识别语言代码 `zn`".into()));
+}
+
+#[test]
+fn baidu_domain_with_glossary() {
+    let toml =
+        "[baidu]\nappid = \"id\"\nkey = \"key\"\ndomain = \"it\"\nglossary = { custom = true }";
+    let mut cf: Config = toml::from_str(toml).unwrap();
+    cf.api = API::Baidu;
+    let err = cf.init_translator().unwrap_err();
+    assert_eq!(err.to_string(),
+               "百度翻译的领域翻译不支持自定义术语库：domain 与 glossary.custom 不能同时设置");
+}
+
+// 主 API 由 API 自己识别原语言：不使用备用的小牛翻译在本地识别的结果，原语言以 auto 发送
+#[test]
+fn auto_detect() {
+    let toml = "fallback = [\"niutrans\"]\n[niutrans]\nkey = \"key\"\n[pseudo]\nmode = \"reverse\"";
+    let mut cf = config(toml, API::Pseudo);
+    cf.src.from = "auto".into();
+    cf.check_languages().unwrap();
+    cf.src.query = "これは日本語の文章です。".into();
+    assert_display_snapshot!(cf.do_single_query().unwrap(), @r###"
+    これは日本語の文章です。
+
+    ⟦。すで章文の語本日はれこ····⟧
+    "###);
+    assert_display_snapshot!(cf.report[0], @"命令行输入：pseudo 第 1 段");
+}
+
+#[test]
+fn dict_without_dictionary() {
+    // 伪本地化不提供词典：--dict 时照常翻译
+    let mut cf = config("[pseudo]\nmode = \"reverse\"", API::Pseudo);
+    cf.src.dict = true;
+    cf.src.query = "apple".into();
+    assert_display_snapshot!(cf.do_single_query().unwrap(), @r###"
+    apple
+
+    ⟦elppa··⟧
+    "###);
+}
+
+// --dict 查词：领域翻译不提供词典，请求发往 endpoint 中通用翻译的路径
+#[test]
+fn dict_via_endpoint() {
+    use crate::translator::tests::StandIn;
+    let stand_in = StandIn::new(vec![
+        r#"{"from":"en","to":"zh","trans_result":[{"src":"apple","dst":"苹果","dict":"{\"lang\":\"1\",\"word_result\":{\"simple_means\":{\"word_name\":\"apple\",\"symbols\":[{\"ph_en\":\"ˈæpl\",\"ph_am\":\"ˈæpl\",\"parts\":[{\"part\":\"n.\",\"means\":[\"苹果\",\"苹果树\"]}]}]}}}"}]}"#.into(),
+    ]);
+    let toml = format!("[baidu]\nappid = \"id\"\nkey = \"key\"\ndomain = \"it\"\nendpoint = \
+                        \"{}/api/trans/vip/fieldtranslate\"",
+                       stand_in.url);
+    let mut cf = config(&toml, API::Baidu);
+    cf.src.dict = true;
+    cf.src.query = "apple".into();
+    assert_display_snapshot!(cf.do_single_query().unwrap(), @r###"
+    苹果
+
+    apple
+    英 [ˈæpl]  美 [ˈæpl]
+    n. 苹果；苹果树
+    "###);
+    let request = stand_in.recv();
+    assert_eq!(request.path, "/api/trans/vip/translate");
+    assert_eq!(request.form("dict"), ["1"]);
+    assert!(request.form("domain").is_empty());
+
+    // 查词失败时照常翻译
+    let stand_in = StandIn::new(vec![
+        r#"{"error_code":"58000","error_msg":"INVALID_CLIENT_IP"}"#.into(),
+        r#"{"from":"en","to":"zh","trans_result":[{"src":"apple","dst":"苹果"}]}"#.into(),
+    ]);
+    let toml = format!("[baidu]\nappid = \"id\"\nkey = \"key\"\nendpoint = \"{}\"", stand_in.url);
+    let mut cf = config(&toml, API::Baidu);
+    cf.src.dict = true;
+    cf.src.query = "apple".into();
+    assert_display_snapshot!(cf.do_single_query().unwrap(), @r###"
+    apple
+
+    苹果
+    "###);
+    assert_eq!(stand_in.recv().form("dict"), ["1"]);
+    assert!(stand_in.recv().form("dict").is_empty());
+}
+
+// 从配置文件的内容构造 `api` 的翻译接口，翻译方向为默认的 en 到 zh
+fn config(toml: &str, api: API) -> Config {
+    let mut cf: Config = toml::from_str(toml).unwrap();
+    cf.api = api;
+    cf.src.from = "en".into();
+    cf.src.to = "zh".into();
+    cf.init_translator().unwrap();
+    cf
 }
 
 #[rustfmt::skip]
@@ -0,0 +1,158 @@
+use crate::{
+    limiter::{limiter, SystemClock},
+    md::Md,
+    retry::{is_network_error, retry},
+};
+use anyhow::{Error, Result};
+use reqwest::blocking::{self, Client};
+use translation_api_cn::{Limit, Retry};
+
+mod alibaba;
+mod azure;
+mod baidu;
+mod caiyun;
+mod command;
+mod deepl;
+mod fallback;
+mod google;
+mod libretranslate;
+mod llm;
+mod niutrans;
+mod pseudo;
+mod tencent;
+mod volcengine;
+mod youdao;
+
+pub use command::Command;
+pub use fallback::{Fallback, Usage};
+pub use pseudo::Pseudo;
+pub use tencent::Sts;
+
+/// 翻译接口
+///
+/// 每种翻译 API 只需要实现 [`translate`][`Translator::translate`]：
+/// 把一批段落翻译成数量相同的译文段落。md 文本的分批与译文的写入由
+/// [`translate_md`][`Translator::translate_md`] 统一处理。
+pub trait Translator: std::fmt::Debug {
+    /// 单次请求时，被限制的“字符”上限。上限为 0 时，不分批请求。
+    fn limit(&self) -> &Limit;
+
+    /// 限流时所使用的帐号标识：同一帐号的请求共享 QPS。
+    fn account(&self) -> String;
+
+    /// 每秒最多发送的请求数。为 0 时不限流。
+    fn qps(&self) -> u8;
+
+    /// 请求失败时的重试策略。
+    fn retry(&self) -> &Retry;
+
+    /// 是否为暂时性的、可以重试的错误。默认只有网络连接失败或者超时才重试。
+    fn is_retriable(&self, err: &Error) -> bool { is_network_error(err) }
+
+    /// 在发送任何请求之前检查语言对。默认不检查，由 API 自己返回错误。
+    fn check_languages(&self, _from: &str, _to: &str) -> Result<()> { Ok(()) }
+
+    /// 原语言为 auto 时，在翻译之前识别一段样本文本的语言，返回语言代码。
+    ///
+    /// 默认返回 None：表示由 API 自己识别，原语言以 auto 发送。
+    /// 不支持 auto 的 API 应该在这里识别出原语言，或者返回错误。
+    fn detect(&self, _text: &str) -> Result<Option<String>> { Ok(None) }
+
+    /// 查词：返回单词或者短语的译文及词典释义，排版后直接输出到终端。
+    ///
+    /// 返回 None 表示 API 不提供词典（默认）或者查不到这个词，照常翻译。
+    fn lookup(&self, _word: &str, _from: &str, _to: &str) -> Result<Option<String>> { Ok(None) }
+
+    /// 翻译一批段落。返回的译文必须与原文段落按顺序一一对应。
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>>;
+
+    /// 限流之后翻译一批段落，并按照 [`retry`][`Translator::retry`] 策略重试：
+    /// 重试时只重新发送这一批段落。
+    ///
+    /// 译文数量与原文段落数量不一致时返回 [`CountMismatch`]，以免译文写入错误的段落。
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        throttle(self, || checked(q, self.translate(q, from, to)?))
+    }
+
+    /// 取出上一次 [`translate_md`][`Translator::translate_md`] 中各段落由哪个 API 翻译。
+    /// 只有 [`Fallback`] 会记录，其余翻译接口返回空。
+    fn take_usage(&self) -> Vec<Usage> { Vec::new() }
+
+    /// 按照 [`limit`][`Translator::limit`] 分批翻译 md 文本，返回写入译文之后的 md 文本。
+    fn translate_md(&self, mut md: Md, from: &str, to: &str) -> Result<String> {
+        let mut output = Vec::new();
+        let mut f = |buf: &str| {
+            let q: Vec<&str> = buf.trim().split('\n').collect();
+            debug!("\nq = {:?}", q);
+            output.extend(self.translate_batch(&q, from, to)?);
+            Ok::<(), Error>(())
+        };
+        match *self.limit() {
+            Limit::Byte(0) | Limit::Char(0) => f(md.extract())?,
+            Limit::Byte(l) => md.bytes_paragraph(l).try_for_each(f)?,
+            Limit::Char(l) => md.chars_paragraph(l).try_for_each(f)?,
+        }
+        debug!("output = {:#?}", output);
+        Ok(md.done(output.iter().map(String::as_str)))
+    }
+}
+
+/// 以 `translator` 的限流和重试策略发送请求：每次尝试之前都按照
+/// [`account`][`Translator::account`] 和 [`qps`][`Translator::qps`] 限流，失败时按照
+/// [`retry`][`Translator::retry`] 策略重试。
+fn throttle<T, R>(translator: &T, mut request: impl FnMut() -> Result<R>) -> Result<R>
+    where T: Translator + ?Sized {
+    retry(translator.retry(),
+          &SystemClock,
+          |e| translator.is_retriable(e),
+          || {
+              limiter().acquire(&translator.account(), translator.qps());
+              request()
+          })
+}
+
+/// 一次请求最多只能发送 `max` 段时，把一批段落拆成多次请求：每次请求单独限流、重试和
+/// 校验译文数量。某次请求失败时，只重新发送这一次请求的段落。
+fn translate_chunks<T>(translator: &T, q: &[&str], max: usize,
+                       mut request: impl FnMut(&[&str]) -> Result<Vec<String>>)
+                       -> Result<Vec<String>>
+    where T: Translator + ?Sized
+{
+    let mut output = Vec::with_capacity(q.len());
+    for q in q.chunks(max) {
+        output.extend(throttle(translator, || checked(q, request(q)?))?);
+    }
+    Ok(output)
+}
+
+/// 校验译文数量与原文段落数量是否一致
+fn checked(q: &[&str], output: Vec<String>) -> Result<Vec<String>> {
+    if output.len() != q.len() {
+        return Err(CountMismatch { expected: q.len(), got: output.len() }.into());
+    }
+    Ok(output)
+}
+
+/// 一批段落的译文数量与原文段落数量不一致
+#[derive(Debug)]
+pub struct CountMismatch {
+    pub expected: usize,
+    pub got:      usize,
+}
+
+impl std::error::Error for CountMismatch {}
+impl std::fmt::Display for CountMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "返回的译文数量不一致：发送 {} 段，收到 {} 段", self.expected, self.got)
+    }
+}
+
+/// 以 post + 表单方式发送
+fn send<T: serde::Serialize + ?Sized>(url: &str, form: &T) -> Result<blocking::Response> {
+    let response = Client::new().post(url).form(form).send()?;
+    debug_assert!(response.error_for_status_ref().is_ok());
+    Ok(response)
+}
+
+#[cfg(test)]
+pub mod tests;
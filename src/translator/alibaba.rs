@@ -0,0 +1,69 @@
+use super::Translator;
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::{Client, Response as HttpResponse};
+use serde_json::from_slice;
+use translation_api_cn::{
+    alibaba::{self, Query, Response, User},
+    language::Provider,
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("alibaba:{}", self.id) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<alibaba::Error>()
+           .map_or_else(|| is_network_error(err), alibaba::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Alibaba.pair(from, to)?;
+        Ok(())
+    }
+
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Alibaba.pair(from, to)?;
+        let q = q.join("\n");
+        let query = Query::new(&q, from, to);
+        let bytes = send(self, &query)?.bytes()?;
+        let response = from_slice::<Response>(&bytes)?;
+        debug!("\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", query, bytes, response);
+        Ok(response.dst_owned()?)
+    }
+}
+
+/// 出错时阿里云返回 4xx/5xx 状态码以及 JSON 格式的错误信息，所以这里不检查状态码。
+fn send(user: &User, query: &Query) -> Result<HttpResponse> {
+    Ok(Client::new().post(user.url().as_ref()).form(&query.sign(user)).send()?)
+}
+
+#[test]
+fn translate_via_endpoint() {
+    use super::tests::StandIn;
+    let stand_in = StandIn::new(vec![r#"{"RequestId":"x","Data":{"Translated":"你好\n世界"}}"#.into(),
+                                     r#"{"RequestId":"x","Code":"Throttling.User","Message":"Request was denied due to user flow control."}"#.into()]);
+    let user = User { id: "id".into(),
+                      key: "key".into(),
+                      endpoint: Some(stand_in.url.clone()),
+                      ..User::default() };
+    assert_eq!(user.translate(&["hello", "world"], "en", "zh").unwrap(), ["你好", "世界"]);
+    let request = stand_in.recv();
+    assert_eq!(request.form("Action"), [alibaba::ACTION]);
+    assert_eq!(request.form("SourceText"), ["hello\nworld"]);
+    assert_eq!(request.form("TargetLanguage"), ["zh"]);
+    assert_eq!(request.form("AccessKeyId"), ["id"]);
+    let mut query = Query::new("hello\nworld", "en", "zh");
+    query.timestamp = request.form("Timestamp").remove(0);
+    query.nonce = request.form("SignatureNonce").remove(0);
+    assert_eq!(request.form("Signature"), [query.sign(&user).signature]);
+
+    let err = user.translate(&["hello"], "en", "zh").unwrap_err();
+    assert!(user.is_retriable(&err), "{err}");
+}
@@ -0,0 +1,52 @@
+use super::{translate_chunks, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::Client;
+use serde_json::from_slice;
+use translation_api_cn::{
+    azure::{self, Query, Response, User, MAX_TEXTS},
+    language::Provider,
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("azure:{}", self.key) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<azure::Error>()
+           .map_or_else(|| is_network_error(err), azure::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Azure.pair(from, to)?;
+        Ok(())
+    }
+
+    /// 单次请求的数组最多 [`MAX_TEXTS`] 个元素，由 [`translate_batch`] 拆分。
+    /// 语言代码转换成 Azure 的代码，比如简体中文 `zh` 转换成 `zh-Hans`。
+    ///
+    /// [`translate_batch`]: Translator::translate_batch
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Azure.pair(from, to)?;
+        let query = Query::new(q, from, to);
+        let builder = Client::new().post(self.url()).query(&query.params(self)).json(&query.body());
+        let bytes = self.header()
+                        .into_iter()
+                        .fold(builder, |b, (k, v)| b.header(k, v))
+                        .send()?
+                        .bytes()?;
+        let response = from_slice::<Response>(&bytes)?;
+        debug!("\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", query, bytes, response);
+        Ok(response.dst_owned()?)
+    }
+
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        translate_chunks(self, q, MAX_TEXTS, |q| self.translate(q, from, to))
+    }
+}
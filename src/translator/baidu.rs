@@ -0,0 +1,90 @@
+use super::{send, throttle, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use serde_json::from_slice;
+use translation_api_cn::{
+    baidu::{self, Query, Response, User},
+    language::Provider,
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("baidu:{}", self.appid) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<baidu::Error>()
+           .map_or_else(|| is_network_error(err), baidu::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Baidu.pair(from, to)?;
+        Ok(())
+    }
+
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Baidu.pair(from, to)?;
+        let q = q.join("\n");
+        let mut query = Query::new(&q, from, to);
+        let bytes = send(self.url(), &{
+                        let sign = query.sign(self);
+                        debug!("sign = {:#?}", sign);
+                        sign
+                    })?.bytes()?;
+        let response = from_slice::<Response>(&bytes)?;
+        debug!("\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", query, bytes, response);
+        Ok(response.dst_owned()?)
+    }
+
+    /// 以 `dict=1` 请求通用翻译接口（领域翻译不提供词典），见 [`User::dict_url`]：查得到
+    /// 词典释义时，在译文之后附上释义；查不到时返回 None，照常翻译。
+    fn lookup(&self, word: &str, from: &str, to: &str) -> Result<Option<String>> {
+        let (from, to) = Provider::Baidu.pair(from, to)?;
+        throttle(self, || {
+            let mut query = Query::new(word, from, to);
+            query.dict = true;
+            let bytes = send(&self.dict_url(), &query.sign(self))?.bytes()?;
+            let response = from_slice::<Response>(&bytes)?;
+            debug!("\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", query, bytes, response);
+            let res = match response {
+                Response::Ok { res, .. } => res,
+                Response::Err(e) => return Err(e.into()),
+            };
+            let Some(entry) = res.first().and_then(|x| x.entry()) else {
+                return Ok(None);
+            };
+            let dst = res.iter().map(|x| x.dst.as_ref()).collect::<Vec<_>>().join("\n");
+            Ok(Some(format!("{dst}\n\n{entry}")))
+        })
+    }
+}
+
+#[test]
+fn translate_via_endpoint() {
+    use super::tests::StandIn;
+    let stand_in = StandIn::new(vec![
+        r#"{"from":"en","to":"zh","trans_result":[{"src":"hello","dst":"你好"},{"src":"world","dst":"世界"}]}"#.into(),
+        r#"{"error_code":"54003","error_msg":"Invalid Access Limit"}"#.into(),
+    ]);
+    let user = User { appid: "id".into(),
+                      key: "key".into(),
+                      domain: Some("it".into()),
+                      endpoint: Some(format!("{}/api/trans/vip/fieldtranslate", stand_in.url)),
+                      ..User::default() };
+    assert_eq!(user.translate(&["hello", "world"], "en", "jp").unwrap(), ["你好", "世界"]);
+    let request = stand_in.recv();
+    assert_eq!(request.path, "/api/trans/vip/fieldtranslate");
+    assert_eq!(request.form("q"), ["hello\nworld"]);
+    assert_eq!(request.form("to"), ["jp"]);
+    assert_eq!(request.form("domain"), ["it"]);
+    assert_eq!(request.form("sign"), [Query::new("hello\nworld", "en", "jp").sign(&user).sign]);
+    assert!(request.form("dict").is_empty());
+
+    let err = user.translate(&["hello"], "en", "zh").unwrap_err();
+    assert!(user.is_retriable(&err), "{err}");
+}
@@ -0,0 +1,63 @@
+use super::Translator;
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::Client;
+use serde_json::from_slice;
+use translation_api_cn::{
+    caiyun::{self, Query, Response, User},
+    language::Provider,
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("caiyun:{}", self.key) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<caiyun::Error>()
+           .map_or_else(|| is_network_error(err), caiyun::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Caiyun.pair(from, to)?;
+        Ok(())
+    }
+
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Caiyun.pair(from, to)?;
+        let query = Query::new(q, from, to)?;
+        let response = Client::new().post(self.url())
+                                    .header("x-authorization", self.authorization())
+                                    .json(&query)
+                                    .send()?;
+        let status = response.status();
+        let bytes = response.bytes()?;
+        debug!("\nquery = {:#?}\nstatus = {}\nbytes = {:?}", query, status, bytes);
+        if !status.is_success() {
+            return Err(caiyun::Error::new(status.as_u16(), &bytes).into());
+        }
+        Ok(from_slice::<Response>(&bytes)?.dst_owned())
+    }
+}
+
+#[test]
+fn translate_via_endpoint() {
+    use super::tests::StandIn;
+    let stand_in =
+        StandIn::new(vec![r#"{"confidence":0.8,"target":["你好","世界"],"rc":0}"#.into()]);
+    let user = User { key: "key".into(),
+                      endpoint: Some(stand_in.url.clone()),
+                      ..User::default() };
+    assert_eq!(user.translate(&["hello", "world"], "auto", "zh").unwrap(), ["你好", "世界"]);
+    let request = stand_in.recv();
+    assert_eq!(request.header("x-authorization"), Some("token key"));
+    assert_eq!(request.json(),
+               serde_json::json!({ "source": ["hello", "world"], "trans_type": "auto2zh",
+                                   "request_id": "bilingual", "detect": true }));
+    assert!(user.translate(&["hello"], "en", "ja").is_err());
+}
@@ -0,0 +1,175 @@
+//! 外部命令：把一批段落写入外部程序的 stdin，再从它的 stdout 按顺序读回译文。
+//!
+//! 用于接入自部署或者离线的翻译引擎（Marian、Argos Translate 或者任意脚本），
+//! 而无需修改 translation-api-cn。
+use super::Translator;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{
+    io::Write,
+    process::{Command as Process, Stdio},
+};
+use translation_api_cn::{Limit, Retry};
+
+/// `[command]` 配置
+#[derive(Debug, Deserialize)]
+pub struct Command {
+    /// 外部程序的路径或者名称
+    pub program: String,
+    /// 程序的参数，其中的 `{from}` 和 `{to}` 会被替换成原语言和目标语言。
+    #[serde(default)]
+    pub args:    Vec<String>,
+    /// 段落在 stdin 与 stdout 中的格式，默认为 jsonl。
+    #[serde(default)]
+    pub mode:    Mode,
+    /// 每批段落的字符上限，默认为 Char(1000)。每批段落启动一次程序。
+    #[serde(default = "default_limit")]
+    pub limit:   Limit,
+    /// 每秒最多启动的次数，默认为 0，即不限流。
+    #[serde(default)]
+    pub qps:     u8,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:   Retry,
+}
+
+fn default_limit() -> Limit { Limit::Char(1000) }
+
+/// 段落的分隔格式
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// 每行一个 JSON 字符串：`"Hello, world!"`。段落中的换行等字符会被转义。
+    #[default]
+    Jsonl,
+    /// 每个段落以 NUL（`\0`）结尾的纯文本。
+    Nul,
+}
+
+impl Mode {
+    /// 把段落编码成写入 stdin 的内容。
+    pub fn encode(self, q: &[&str]) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        for s in q {
+            match self {
+                Mode::Jsonl => {
+                    serde_json::to_writer(&mut buf, s)?;
+                    buf.push(b'\n');
+                }
+                Mode::Nul => {
+                    buf.extend_from_slice(s.as_bytes());
+                    buf.push(b'\0');
+                }
+            }
+        }
+        Ok(buf)
+    }
+
+    /// 从 stdout 的内容中解码译文：忽略 jsonl 中的空行，以及 nul 末尾多余的分隔符。
+    pub fn decode(self, stdout: &[u8]) -> Result<Vec<String>> {
+        let stdout = std::str::from_utf8(stdout).context("外部程序的输出不是 UTF-8")?;
+        match self {
+            Mode::Jsonl => stdout.lines()
+                                 .filter(|l| !l.trim().is_empty())
+                                 .map(|l| {
+                                     serde_json::from_str(l).with_context(|| {
+                                                                format!("外部程序的输出不是 JSON \
+                                                                         字符串：{l}")
+                                                            })
+                                 })
+                                 .collect(),
+            Mode::Nul => Ok(stdout.strip_suffix('\0')
+                                  .unwrap_or(stdout)
+                                  .split('\0')
+                                  .map(String::from)
+                                  .collect()),
+        }
+    }
+}
+
+impl Command {
+    /// 填充了语言的参数
+    pub fn args(&self, from: &str, to: &str) -> Vec<String> {
+        self.args
+            .iter()
+            .map(|a| a.replace("{from}", from).replace("{to}", to))
+            .collect()
+    }
+}
+
+impl Translator for Command {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("command:{}", self.program) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    /// 外部程序以非 0 状态码退出时返回错误。译文的数量由 [`Translator::translate_batch`]
+    /// 校验。
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let input = self.mode.encode(q)?;
+        let mut child = Process::new(&self.program).args(self.args(from, to))
+                                                   .stdin(Stdio::piped())
+                                                   .stdout(Stdio::piped())
+                                                   .stderr(Stdio::piped())
+                                                   .spawn()
+                                                   .with_context(|| {
+                                                       format!("无法启动外部程序 `{}`",
+                                                               self.program)
+                                                   })?;
+        // 在另一个线程中写入 stdin，以免程序的输出填满管道时互相等待
+        let mut stdin = child.stdin.take().context("无法打开外部程序的 stdin")?;
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child.wait_with_output()?;
+        // 程序可能不读完 stdin 就退出，此时的写入错误以退出状态为准
+        let written = writer.join().map_err(|_| anyhow::anyhow!("写入 stdin 的线程异常退出"))?;
+        debug!("\nq = {:?}\noutput = {:?}", q, output);
+        anyhow::ensure!(output.status.success(),
+                        "外部程序 `{}` 异常退出：{}\n{}",
+                        self.program,
+                        output.status,
+                        String::from_utf8_lossy(&output.stderr));
+        written.context("无法写入外部程序的 stdin")?;
+        self.mode.decode(&output.stdout)
+    }
+}
+
+// 以普通的 shell 脚本作为翻译引擎
+#[cfg(unix)]
+#[test]
+fn command_translation() {
+    use super::tests::SAMPLE;
+    use crate::md::Md;
+    let command = |toml: &str| -> Command { toml::from_str(toml).unwrap() };
+    let nul = command("program = \"sh\"\nargs = [\"-c\", \"tr a-z A-Z\"]\nmode = \"nul\"");
+    insta::assert_display_snapshot!(nul.translate_md(Md::new(SAMPLE), "en", "zh").unwrap(), @r###"
+    # Title
+
+    # TITLE
+
+    Hello, `world`!
+
+    HELLO, `WORLD`!
+
+    |a	A|b	B|
+    |---|---|
+    |c	C|d	D|
+    "###);
+    // jsonl：每批段落启动一次程序，`{to}` 被替换成目标语言
+    let jsonl = command(
+                        r#"program = "sh"
+args = ["-c", "sed 's/\"$/ {to}\"/'"]"#,
+    );
+    assert_eq!(jsonl.args("en", "zh"), ["-c", r#"sed 's/"$/ zh"/'"#]);
+    assert_eq!(jsonl.translate(&["a\nb", "c"], "en", "zh").unwrap(), ["a\nb zh", "c zh"]);
+
+    let script =
+        |script: &str| command(&format!("program = \"sh\"\nargs = [\"-c\", \"{script}\"]"));
+    let err = script("cat >/dev/null; echo oops >&2; exit 3").translate(&["a"], "en", "zh")
+                                                             .unwrap_err();
+    assert!(err.to_string().contains("oops"), "{err}");
+    let err = script("head -n 1").translate_batch(&["a", "b"], "en", "zh").unwrap_err();
+    assert_eq!(err.to_string(), "返回的译文数量不一致：发送 2 段，收到 1 段");
+}
@@ -0,0 +1,54 @@
+use super::{translate_chunks, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::Client;
+use serde_json::from_slice;
+use translation_api_cn::{
+    deepl::{self, Query, Response, User, MAX_TEXTS},
+    language::Provider,
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("deepl:{}", self.key) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<deepl::Error>()
+           .map_or_else(|| is_network_error(err), deepl::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::DeepL.pair(from, to)?;
+        Ok(())
+    }
+
+    /// 每段文本作为一个 `text` 字段，单次请求最多 [`MAX_TEXTS`] 个，由 [`translate_batch`]
+    /// 拆分。
+    ///
+    /// [`translate_batch`]: Translator::translate_batch
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::DeepL.pair(from, to)?;
+        let query = Query::new(q, from, to);
+        let response = Client::new().post(self.url())
+                                    .header("Authorization", self.authorization())
+                                    .form(&query.form(self))
+                                    .send()?;
+        let status = response.status();
+        let bytes = response.bytes()?;
+        debug!("\nquery = {:#?}\nstatus = {}\nbytes = {:?}", query, status, bytes);
+        if !status.is_success() {
+            return Err(deepl::Error::new(status.as_u16(), &bytes).into());
+        }
+        Ok(from_slice::<Response>(&bytes)?.dst_owned())
+    }
+
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        translate_chunks(self, q, MAX_TEXTS, |q| self.translate(q, from, to))
+    }
+}
@@ -0,0 +1,213 @@
+//! 备用 API：主 API 翻译某批段落失败时，把这批段落依次交给备用 API 翻译，
+//! 使得文档仍然能够完成翻译。
+use super::Translator;
+use anyhow::{Context, Result};
+use std::sync::Mutex;
+use translation_api_cn::{Limit, Retry};
+
+/// 按顺序尝试的一组翻译接口：第一个为主 API，其余为备用 API。
+///
+/// 每个 API 在失败之前，都会按照自己的限流和重试策略请求；
+/// 重试之后仍然失败（包括余额不足等不可重试的错误）时，才交给下一个 API。
+#[derive(Debug)]
+pub struct Fallback {
+    chain: Vec<(&'static str, Box<dyn Translator>)>,
+    limit: Limit,
+    retry: Retry,
+    usage: Mutex<Vec<Usage>>,
+}
+
+/// 连续若干段落由同一个 API 翻译
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    pub api:   &'static str,
+    /// 第一个段落的序号，从 1 开始
+    pub start: usize,
+    /// 段落数量
+    pub len:   usize,
+}
+
+impl std::fmt::Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.len {
+            1 => write!(f, "{} 第 {} 段", self.api, self.start),
+            _ => write!(f, "{} 第 {}-{} 段", self.api, self.start, self.start + self.len - 1),
+        }
+    }
+}
+
+impl Fallback {
+    /// `chain` 不应为空。分批的上限取所有 API 中最严格的，以免备用 API 因请求过长而失败。
+    pub fn new(chain: Vec<(&'static str, Box<dyn Translator>)>) -> Self {
+        let limit = strictest(chain.iter().map(|(_, t)| t.limit()));
+        Self { chain,
+               limit,
+               retry: Retry { attempts: 1, ..Retry::default() },
+               usage: Mutex::default() }
+    }
+
+    fn record(&self, api: &'static str, len: usize) {
+        let mut usage = self.usage.lock().unwrap();
+        let start = usage.last().map_or(1, |u| u.start + u.len);
+        match usage.last_mut() {
+            Some(last) if last.api == api => last.len += len,
+            _ => usage.push(Usage { api, start, len }),
+        }
+    }
+}
+
+/// 上限为 0 表示不分批，不参与比较。存在 Byte 上限时取 Byte：同样的数值，
+/// 字节数不少于字符数，因此满足 Byte 上限也就满足 Char 上限。
+fn strictest<'l>(limits: impl Iterator<Item = &'l Limit>) -> Limit {
+    let (mut min, mut byte) = (0, false);
+    for limit in limits {
+        let l = limit.limit();
+        if l != 0 && (min == 0 || l < min) {
+            min = l;
+        }
+        byte |= matches!(limit, Limit::Byte(l) if *l != 0);
+    }
+    if byte {
+        Limit::Byte(min)
+    } else {
+        Limit::Char(min)
+    }
+}
+
+impl Translator for Fallback {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("fallback:{}", self.chain[0].1.account()) }
+
+    /// 限流由每个 API 自己负责。
+    fn qps(&self) -> u8 { 0 }
+
+    /// 重试由每个 API 自己负责。
+    fn retry(&self) -> &Retry { &self.retry }
+
+    /// 每个 API 都必须支持这个语言对。
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        for (api, translator) in &self.chain {
+            translator.check_languages(from, to)
+                      .with_context(|| format!("{api} 无法翻译"))?;
+        }
+        Ok(())
+    }
+
+    /// 由主 API 识别原语言，所有 API 都按照识别出的语言翻译；主 API 返回 None 时，原语言以
+    /// auto 发送。只有识别失败时，才依次改用备用 API 识别。
+    fn detect(&self, text: &str) -> Result<Option<String>> {
+        let mut chain = self.chain.iter().peekable();
+        while let Some((api, translator)) = chain.next() {
+            match translator.detect(text) {
+                Ok(lang) => return Ok(lang),
+                Err(err) => match chain.peek() {
+                    Some((next, _)) => warn!("{api} 识别原语言失败，改用 {next} 识别：\n{err}"),
+                    None => return Err(err),
+                },
+            }
+        }
+        Ok(None)
+    }
+
+    /// 使用第一个提供词典的 API 的结果。
+    fn lookup(&self, word: &str, from: &str, to: &str) -> Result<Option<String>> {
+        for (api, translator) in &self.chain {
+            match translator.lookup(word, from, to) {
+                Ok(Some(entry)) => return Ok(Some(entry)),
+                Ok(None) => (),
+                Err(err) => warn!("{api} 查词失败：\n{err}"),
+            }
+        }
+        Ok(None)
+    }
+
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        self.translate(q, from, to)
+    }
+
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let mut chain = self.chain.iter().peekable();
+        while let Some((api, translator)) = chain.next() {
+            match translator.translate_batch(q, from, to) {
+                Ok(output) => {
+                    self.record(api, q.len());
+                    return Ok(output);
+                }
+                Err(err) => match chain.peek() {
+                    Some((next, _)) => {
+                        warn!("{api} 翻译失败，改用 {next} 翻译这 {} 段：\n{err}", q.len())
+                    }
+                    None => return Err(err),
+                },
+            }
+        }
+        anyhow::bail!("未指定翻译 API")
+    }
+
+    fn take_usage(&self) -> Vec<Usage> { std::mem::take(&mut self.usage.lock().unwrap()) }
+}
+
+// 主 API 翻译含有 world 的那批段落时失败，这批段落改由备用 API 翻译
+#[cfg(unix)]
+#[test]
+fn fallback_chain() {
+    use super::{tests::SAMPLE, Command, Pseudo};
+    use crate::md::Md;
+    let toml = r#"program = "sh"
+args = ["-c", "input=$(cat); case $input in *world*) exit 1;; esac; echo \"$input\" | tr a-z A-Z"]
+limit = { chars = 10 }"#;
+    let command: Command = toml::from_str(toml).unwrap();
+    let pseudo: Pseudo =
+        toml::from_str("mode = \"reverse\"\nbracket = false\nexpansion = 0").unwrap();
+    let fallback =
+        Fallback::new(vec![("command", Box::new(command)), ("pseudo", Box::new(pseudo))]);
+    insta::assert_display_snapshot!(fallback.translate_md(Md::new(SAMPLE), "en", "zh").unwrap(), @r###"
+    # Title
+
+    # TITLE
+
+    Hello, `world`!
+
+    !`dlrow` ,olleH
+
+    |a	A|b	B|
+    |---|---|
+    |c	C|d	D|
+    "###);
+    let usage = fallback.take_usage().iter().map(Usage::to_string).collect::<Vec<_>>();
+    assert_eq!(usage, ["command 第 1 段", "pseudo 第 2 段", "command 第 3-6 段"]);
+    assert!(fallback.take_usage().is_empty());
+}
+
+// 主 API 返回 None 时不使用备用 API 的识别结果，主 API 识别失败时才使用
+#[test]
+fn fallback_detect() {
+    use super::{tests::NO_RETRY, Pseudo};
+    // 识别出的语言；None 表示识别失败
+    #[derive(Debug)]
+    struct Detect(Option<&'static str>);
+    impl Translator for Detect {
+        fn limit(&self) -> &Limit { &Limit::Char(0) }
+
+        fn account(&self) -> String { "detect".into() }
+
+        fn qps(&self) -> u8 { 0 }
+
+        fn retry(&self) -> &Retry { &NO_RETRY }
+
+        fn detect(&self, _: &str) -> Result<Option<String>> {
+            self.0.map(String::from).map(Some).context("无法识别")
+        }
+
+        fn translate(&self, q: &[&str], _: &str, _: &str) -> Result<Vec<String>> {
+            Ok(q.iter().map(|s| s.to_string()).collect())
+        }
+    }
+    let fallback = |primary: Box<dyn Translator>| {
+        Fallback::new(vec![("primary", primary), ("detect", Box::new(Detect(Some("ja"))))])
+    };
+    assert_eq!(fallback(Box::<Pseudo>::default()).detect("hi").unwrap(), None);
+    assert_eq!(fallback(Box::new(Detect(Some("en")))).detect("hi").unwrap().unwrap(), "en");
+    assert_eq!(fallback(Box::new(Detect(None))).detect("hi").unwrap().unwrap(), "ja");
+}
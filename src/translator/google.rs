@@ -0,0 +1,50 @@
+use super::{translate_chunks, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::Client;
+use serde_json::from_slice;
+use translation_api_cn::{
+    google::{self, Query, Response, User, MAX_TEXTS},
+    language::Provider,
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("google:{}", self.key) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<google::Error>()
+           .map_or_else(|| is_network_error(err), google::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Google.pair(from, to)?;
+        Ok(())
+    }
+
+    /// 每段文本作为一个 `q` 字段，单次请求最多 [`MAX_TEXTS`] 个，由 [`translate_batch`] 拆分。
+    ///
+    /// [`translate_batch`]: Translator::translate_batch
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Google.pair(from, to)?;
+        let query = Query::new(q, from, to);
+        let bytes = Client::new().post(self.url())
+                                 .query(&[("key", &self.key)])
+                                 .form(&query)
+                                 .send()?
+                                 .bytes()?;
+        let response = from_slice::<Response>(&bytes)?;
+        debug!("\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", query, bytes, response);
+        Ok(response.dst_owned()?)
+    }
+
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        translate_chunks(self, q, MAX_TEXTS, |q| self.translate(q, from, to))
+    }
+}
@@ -0,0 +1,43 @@
+use super::Translator;
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::Client;
+use serde_json::from_slice;
+use translation_api_cn::{
+    language::Provider,
+    libretranslate::{self, Query, Response, User},
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("libretranslate:{}", self.url) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<libretranslate::Error>()
+           .map_or_else(|| is_network_error(err), libretranslate::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::LibreTranslate.pair(from, to)?;
+        Ok(())
+    }
+
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::LibreTranslate.pair(from, to)?;
+        let query = Query::new(q, from, to, self);
+        let response = Client::new().post(self.url()).json(&query).send()?;
+        let status = response.status();
+        let bytes = response.bytes()?;
+        debug!("\nquery = {:#?}\nstatus = {}\nbytes = {:?}", query, status, bytes);
+        if !status.is_success() {
+            return Err(libretranslate::Error::new(status.as_u16(), &bytes).into());
+        }
+        Ok(from_slice::<Response>(&bytes)?.dst_owned())
+    }
+}
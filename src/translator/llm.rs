@@ -0,0 +1,76 @@
+use super::{CountMismatch, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::Client;
+use serde_json::from_slice;
+use translation_api_cn::{
+    llm::{self, Request, Response, User},
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("llm:{}:{}", self.base_url, self.model) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    /// 模型回复的段落数量不一致时也可以重试：重新请求可能得到合格的回复。
+    fn is_retriable(&self, err: &Error) -> bool {
+        if err.is::<CountMismatch>() {
+            return true;
+        }
+        err.downcast_ref::<llm::Error>()
+           .map_or_else(|| is_network_error(err), llm::Error::is_retriable)
+    }
+
+    /// 段落以带编号的 JSON 数组发送，回复按编号排序。
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let request = Request::new(self, q, from, to);
+        let mut builder = Client::new().post(self.url()).json(&request);
+        if !self.api_key.is_empty() {
+            builder = builder.bearer_auth(&self.api_key);
+        }
+        let response = builder.send()?;
+        let status = response.status();
+        let bytes = response.bytes()?;
+        debug!("\nrequest = {:#?}\nstatus = {}\nbytes = {:?}", request, status, bytes);
+        if !status.is_success() {
+            return Err(llm::Error::new(status.as_u16(), &bytes).into());
+        }
+        Ok(from_slice::<Response>(&bytes)?.dst_owned()?)
+    }
+}
+
+#[test]
+fn llm_translation() {
+    use super::tests::{StandIn, NO_RETRY};
+    let reply = |content: &str| {
+        serde_json::json!({ "choices": [{ "message": { "role": "assistant", "content": content } }] }).to_string()
+    };
+    let stand_in = StandIn::new(vec![reply(r#"[{"id":2,"text":"世界"},{"id":1,"text":"你好"}]"#),
+                                     reply(r#"[{"id":1,"text":"你好"}]"#)]);
+    let user = User { base_url: format!("{}/v1", stand_in.url),
+                      model: "stand-in".into(),
+                      api_key: "sk".into(),
+                      prompt: "from {from} to {to}".into(),
+                      retry: NO_RETRY.clone(),
+                      ..Default::default() };
+
+    assert_eq!(user.translate(&["hello", "world"], "en", "zh").unwrap(), ["你好", "世界"]);
+    let request = stand_in.recv();
+    assert_eq!(request.path, "/v1/chat/completions");
+    assert_eq!(request.header("authorization"), Some("Bearer sk"));
+    let body = request.json();
+    assert_eq!(body["model"], "stand-in");
+    assert_eq!(body["messages"][0]["content"], "from en to zh");
+    assert_eq!(body["messages"][1]["content"],
+               r#"[{"id":1,"text":"hello"},{"id":2,"text":"world"}]"#);
+
+    // 回复的段落数量不一致：可以重试
+    let err = user.translate_batch(&["hello", "world"], "en", "zh").unwrap_err();
+    assert_eq!(err.to_string(), "返回的译文数量不一致：发送 2 段，收到 1 段");
+    assert!(user.is_retriable(&err));
+}
@@ -0,0 +1,55 @@
+use super::{send, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Context, Error, Result};
+use serde_json::from_slice;
+use translation_api_cn::{
+    language::{Language, Provider},
+    niutrans::{self, Query, Response, User},
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("niutrans:{}", self.key) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<niutrans::Error>()
+           .map_or_else(|| is_network_error(err), niutrans::Error::is_retriable)
+    }
+
+    /// 原语言为 auto 时，只检查目标语言：原语言在翻译之前由 [`detect`][`Translator::detect`]
+    /// 识别。
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        if from.eq_ignore_ascii_case("auto") {
+            Provider::Niutrans.target(to)?;
+        } else {
+            Provider::Niutrans.pair(from, to)?;
+        }
+        Ok(())
+    }
+
+    /// 小牛翻译不支持 auto，在本地根据文字和常用词识别。
+    fn detect(&self, text: &str) -> Result<Option<String>> {
+        let lang = Language::detect(text).context("无法识别原语言，请使用 -f 指定")?;
+        Ok(Some(lang.code().into()))
+    }
+
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Niutrans.pair(from, to)?;
+        let q = q.join("\n");
+        let query = Query::new(&q, from, to);
+        let bytes = send(self.url(), &{
+                        let form = query.form(self);
+                        debug!("form = {:#?}", form);
+                        form
+                    })?.bytes()?;
+        let response = from_slice::<Response>(&bytes)?;
+        debug!("\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", query, bytes, response);
+        Ok(response.dst_owned()?)
+    }
+}
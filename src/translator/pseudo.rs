@@ -0,0 +1,127 @@
+//! 伪本地化（pseudo-localization）：不调用翻译 API，也无需帐号，按照固定的规则变换原文。
+//!
+//! 用于在无网络的环境下测试从提取段落、分批到写入译文的整个流程，
+//! 以及表格布局和输出文件的处理。
+use super::Translator;
+use anyhow::Result;
+use serde::Deserialize;
+use translation_api_cn::{Limit, Retry};
+
+/// `[pseudo]` 配置
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Pseudo {
+    /// 变换方式，默认为 accent。
+    pub mode:      Mode,
+    /// 是否以 `⟦` 和 `⟧` 包裹译文，默认为 true。（不使用 `[]`，因为它们在 md 中会被转义）
+    pub bracket:   bool,
+    /// 译文相对原文的扩展比例，以 `·` 填充，默认为 0.3。
+    pub expansion: f32,
+    /// 每批段落的字符上限，默认为 Char(1000)。
+    pub limit:     Limit,
+    #[serde(skip)]
+    retry:         Retry,
+}
+
+/// 伪本地化的变换方式
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// 把拉丁字母替换成带重音符号的字母：`Hello` => `Ĥéļļó`
+    Accent,
+    /// 反转字符顺序：`Hello` => `olleH`
+    Reverse,
+}
+
+impl Default for Pseudo {
+    fn default() -> Self {
+        Self { mode:      Mode::Accent,
+               bracket:   true,
+               expansion: 0.3,
+               limit:     Limit::Char(1000),
+               retry:     Retry { attempts: 1, ..Retry::default() }, }
+    }
+}
+
+impl Pseudo {
+    /// 变换一个段落。
+    pub fn transform(&self, s: &str) -> String {
+        if s.is_empty() {
+            return String::new();
+        }
+        let mut out: String = match self.mode {
+            Mode::Accent => s.chars().map(accent).collect(),
+            Mode::Reverse => s.chars().rev().collect(),
+        };
+        let pad = (s.chars().count() as f32 * self.expansion).ceil() as usize;
+        out.push_str(&"·".repeat(pad));
+        if self.bracket {
+            format!("⟦{out}⟧")
+        } else {
+            out
+        }
+    }
+}
+
+#[rustfmt::skip]
+fn accent(c: char) -> char {
+    match c {
+        'a' => 'á', 'c' => 'ç', 'e' => 'é', 'g' => 'ĝ', 'h' => 'ĥ', 'i' => 'í', 'j' => 'ĵ',
+        'k' => 'ķ', 'l' => 'ļ', 'n' => 'ñ', 'o' => 'ó', 'r' => 'ŕ', 's' => 'š', 't' => 'ţ',
+        'u' => 'ú', 'w' => 'ŵ', 'y' => 'ý', 'z' => 'ž',
+        'A' => 'Á', 'C' => 'Ç', 'E' => 'É', 'G' => 'Ĝ', 'H' => 'Ĥ', 'I' => 'Í', 'J' => 'Ĵ',
+        'K' => 'Ķ', 'L' => 'Ļ', 'N' => 'Ñ', 'O' => 'Ó', 'R' => 'Ŕ', 'S' => 'Š', 'T' => 'Ţ',
+        'U' => 'Ú', 'W' => 'Ŵ', 'Y' => 'Ý', 'Z' => 'Ž',
+        _ => c,
+    }
+}
+
+impl Translator for Pseudo {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { "pseudo".into() }
+
+    fn qps(&self) -> u8 { 0 }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn translate(&self, q: &[&str], _: &str, _: &str) -> Result<Vec<String>> {
+        Ok(q.iter().map(|s| self.transform(s)).collect())
+    }
+}
+
+#[test]
+fn pseudo_localization() {
+    use super::tests::SAMPLE;
+    use crate::md::Md;
+    let output = |toml: &str| {
+        let pseudo: Pseudo = toml::from_str(toml).unwrap();
+        pseudo.translate_md(Md::new(SAMPLE), "en", "zh").unwrap()
+    };
+    insta::assert_display_snapshot!(output("limit = { chars = 10 }"), @r###"
+    # Title
+
+    # ⟦Ţíţļé··⟧
+
+    Hello, `world`!
+
+    ⟦Ĥéļļó, `ŵóŕļd`!·····⟧
+
+    |a	⟦á·⟧|b	⟦b·⟧|
+    |------------|-----------|
+    |c	⟦ç·⟧|d	⟦d·⟧|
+    "###);
+    insta::assert_display_snapshot!(output("mode = \"reverse\"\nbracket = false\nexpansion = 0"), @r###"
+    # Title
+
+    # eltiT
+
+    Hello, `world`!
+
+    !`dlrow` ,olleH
+
+    |a	a|b	b|
+    |---|---|
+    |c	c|d	d|
+    "###);
+}
@@ -0,0 +1,348 @@
+use super::{checked, throttle, Translator};
+use crate::{limiter::limiter, retry::is_network_error};
+use anyhow::{Context, Error, Result};
+use reqwest::blocking::{self, Client};
+use serde_json::from_slice;
+use std::{
+    process::Command as Process,
+    sync::{atomic::Ordering, Mutex, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use translation_api_cn::{
+    language::Provider,
+    tencent::{
+        Action, Credential, DetectQuery, DetectResponse, Header, Query, Refresh, Response,
+        ResponseError, SkewedClock, TextQuery, TextResponse, User,
+    },
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("tencent:{}", self.id) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<ResponseError>()
+           .map_or_else(|| is_network_error(err), ResponseError::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Tencent.pair(from, to)?;
+        Ok(())
+    }
+
+    /// 调用 LanguageDetect 接口识别原语言：与翻译共享限流和重试策略。
+    fn detect(&self, text: &str) -> Result<Option<String>> {
+        throttle(self, || detect_language(self, self, text))
+    }
+
+    /// 以 TextTranslateBatch 批量翻译，不处理不翻译文本，见 [`translate_split`]。
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Tencent.pair(from, to)?;
+        translate_list(self, self, q, from, to)
+    }
+
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        translate_split(self, q, from, to)
+    }
+}
+
+/// 含有不翻译文本的段落使用 TextTranslate 逐段请求：[`User`] 与 [`Sts`] 共用
+/// [`translate_split`]。
+trait TextTranslate: Translator {
+    /// 段落中是否有不翻译文本（`glossary.untranslated`）
+    fn has_untranslated(&self, q: &str) -> bool;
+
+    /// 以 TextTranslate 翻译一段文本，保护其中的不翻译文本
+    fn translate_text(&self, q: &str, from: &str, to: &str) -> Result<String>;
+}
+
+impl TextTranslate for User {
+    fn has_untranslated(&self, q: &str) -> bool { self.untranslated(q).is_some() }
+
+    fn translate_text(&self, q: &str, from: &str, to: &str) -> Result<String> {
+        translate_single(self, self, q, from, to)
+    }
+}
+
+/// 含有不翻译文本的段落拆分出来逐段请求，其余段落仍然批量请求。
+///
+/// 每个请求单独限流和重试：某个请求失败时，只重新发送这个请求。
+fn translate_split<T: TextTranslate>(translator: &T, q: &[&str], from: &str, to: &str)
+                                     -> Result<Vec<String>> {
+    let (single, batch): (Vec<_>, Vec<_>) =
+        (0..q.len()).partition(|&i| translator.has_untranslated(q[i]));
+    let mut output = vec![String::new(); q.len()];
+    if !batch.is_empty() {
+        let paragraphs: Vec<&str> = batch.iter().map(|&i| q[i]).collect();
+        let dst = throttle(translator, || {
+            checked(&paragraphs, translator.translate(&paragraphs, from, to)?)
+        })?;
+        for (i, dst) in batch.into_iter().zip(dst) {
+            output[i] = dst;
+        }
+    }
+    for i in single {
+        output[i] = throttle(translator, || translator.translate_text(q[i], from, to))?;
+    }
+    Ok(output)
+}
+
+// 以下函数以 `user` 的密钥签名，以 `translator` 的帐号标识限流：Sts 的帐号标识不随
+// 临时密钥变化。
+
+/// 以 LanguageDetect 识别原语言
+fn detect_language<T>(translator: &T, user: &User, text: &str) -> Result<Option<String>>
+    where T: Translator + ?Sized {
+    let query = DetectQuery::new(text, user.projectid);
+    let bytes = request(translator, user, &query)?;
+    let response = from_slice::<DetectResponse>(&bytes)?;
+    debug!("\nresponse = {:#?}", response);
+    Ok(Some(response.lang()?.to_owned()))
+}
+
+/// 以 TextTranslateBatch 批量翻译
+fn translate_list<T>(translator: &T, user: &User, q: &[&str], from: &str, to: &str)
+                     -> Result<Vec<String>>
+    where T: Translator + ?Sized {
+    let query = Query::new(q, from, to, user.projectid);
+    let bytes = request(translator, user, &query)?;
+    let response = from_slice::<Response>(&bytes)?;
+    debug!("\nresponse = {:#?}", response);
+    Ok(response.dst_owned()?)
+}
+
+/// 以 TextTranslate 翻译一段文本，保护其中的不翻译文本
+fn translate_single<T>(translator: &T, user: &User, q: &str, from: &str, to: &str) -> Result<String>
+    where T: Translator + ?Sized {
+    let (from, to) = Provider::Tencent.pair(from, to)?;
+    let mut query = TextQuery::new(q, from, to, user.projectid);
+    query.untranslated = user.untranslated(q);
+    let bytes = request(translator, user, &query)?;
+    let response = from_slice::<TextResponse>(&bytes)?;
+    debug!("\nresponse = {:#?}", response);
+    Ok(response.dst_owned()?)
+}
+
+/// 签名并发送请求，返回响应体。
+///
+/// 本地时间与服务器时间相差超过五分钟时，腾讯云返回签名过期（AuthFailure.SignatureExpire）：
+/// 此时根据响应的 Date 头校正 [`User::offset`]，重新签名并再发送一次。再次发送同样受限流。
+fn request<T, Q>(translator: &T, user: &User, query: &Q) -> Result<Vec<u8>>
+    where T: Translator + ?Sized,
+          Q: Action + std::fmt::Debug
+{
+    let clock = user.clock();
+    let mut header = Header::with_clock(user, query, &clock);
+    let response = send(&mut header)?;
+    let date = response.headers()
+                       .get(reqwest::header::DATE)
+                       .and_then(|date| date.to_str().ok())
+                       .and_then(SkewedClock::from_date);
+    let bytes = response.bytes()?.to_vec();
+    debug!("\nquery = {:#?}\nheader = {:#?}\nbytes = {:?}", query, header, bytes);
+    let expired = ResponseError::from_response(&bytes).is_some_and(|e| e.is_signature_expire());
+    match date {
+        // Date 只精确到秒：偏移量没有明显变化时，重新签名也无济于事
+        Some(server) if expired && (server.offset - clock.offset).abs() > 1 => {
+            warn!("本地时间与腾讯云服务器时间相差 {} 秒，校正时间后重新签名", server.offset);
+            user.offset.store(server.offset, Ordering::Relaxed);
+            limiter().acquire(&translator.account(), translator.qps());
+            let mut header = Header::with_clock(user, query, &server);
+            Ok(send(&mut header)?.bytes()?.to_vec())
+        }
+        _ => Ok(bytes),
+    }
+}
+
+#[rustfmt::skip]
+fn send<Q: Action>(header: &mut Header<'_, '_, Q>) -> Result<blocking::Response> {
+    header.authorization()?; // 更改 query 或者 user 时必须重新生成验证信息
+    let map = {
+        use reqwest::header::{HeaderName, HeaderValue};
+        use std::str::FromStr;
+        header.header()
+              .into_iter()
+              .filter_map(|(k, v)| match (HeaderName::from_str(k), HeaderValue::from_str(v)) {
+                  (Ok(key), Ok(value)) => Some((key, value)),
+                  _ => None,
+              }) // 遇到 Err 时，把 Ok 的部分 collect
+              .collect()
+    };
+    Client::new().post(header.user.url().as_ref()).headers(map).json(header.query).send().map_err(|e| e.into())
+}
+
+/// 在临时密钥过期之前多少秒重新读取
+const MARGIN: i64 = 300;
+/// 临时密钥没有过期时间时，每隔多少秒重新读取
+const INTERVAL: i64 = 600;
+
+/// 使用临时密钥的腾讯云：每次请求之前检查临时密钥是否即将过期，按照 [`Refresh`]
+/// 重新读取，因此长时间的批量翻译不会因为临时密钥过期而中断。
+///
+/// 临时密钥的 SecretId 每次读取都会变化，所以所有请求都以固定的 `tencent:sts` 限流，
+/// 而不使用内部 [`User`] 的帐号标识。
+#[derive(Debug)]
+pub struct Sts {
+    user:    RwLock<User>,
+    refresh: Refresh,
+    limit:   Limit,
+    retry:   Retry,
+    qps:     u8,
+    /// 下一次读取临时密钥的时间（Unix 时间戳，单位为秒）
+    next:    Mutex<i64>,
+}
+
+impl Sts {
+    pub fn new(user: User, refresh: Refresh) -> Self {
+        Self { limit: user.limit.clone(),
+               retry: user.retry.clone(),
+               qps: user.qps,
+               user: RwLock::new(user),
+               refresh,
+               next: Mutex::new(0) }
+    }
+
+    fn refresh(&self) -> Result<()> {
+        let mut next = self.next.lock().unwrap();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        if now < *next {
+            return Ok(());
+        }
+        let credential = read(&self.refresh).context("无法读取腾讯云的临时密钥")?;
+        *next = credential.expired.map_or(now + INTERVAL, |t| t - MARGIN);
+        if *next <= now {
+            warn!("腾讯云的临时密钥将在 {} 秒内过期", credential.expired.unwrap_or(now) - now);
+        }
+        debug!("腾讯云的临时密钥已更新，过期时间：{:?}", credential.expired);
+        self.user.write().unwrap().set_credential(credential);
+        Ok(())
+    }
+}
+
+/// 读取文件或者运行命令，解析临时密钥
+fn read(refresh: &Refresh) -> Result<Credential> {
+    let bytes = match refresh {
+        Refresh::File(path) => {
+            std::fs::read(path).with_context(|| format!("无法读取 {}", path.display()))?
+        }
+        Refresh::Command(command) => {
+            let (program, args) = command.split_first().context("refresh 的 command 不应为空")?;
+            let output =
+                Process::new(program).args(args)
+                                     .output()
+                                     .with_context(|| format!("无法启动外部程序 `{program}`"))?;
+            anyhow::ensure!(output.status.success(),
+                            "外部程序 `{}` 异常退出：{}\n{}",
+                            program,
+                            output.status,
+                            String::from_utf8_lossy(&output.stderr));
+            output.stdout
+        }
+    };
+    Ok(from_slice(&bytes)?)
+}
+
+impl Translator for Sts {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { String::from("tencent:sts") }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool { self.user.read().unwrap().is_retriable(err) }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        self.user.read().unwrap().check_languages(from, to)
+    }
+
+    fn detect(&self, text: &str) -> Result<Option<String>> {
+        throttle(self, || {
+            self.refresh()?;
+            detect_language(self, &self.user.read().unwrap(), text)
+        })
+    }
+
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        self.refresh()?;
+        let (from, to) = Provider::Tencent.pair(from, to)?;
+        translate_list(self, &self.user.read().unwrap(), q, from, to)
+    }
+
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        translate_split(self, q, from, to)
+    }
+}
+
+impl TextTranslate for Sts {
+    fn has_untranslated(&self, q: &str) -> bool { self.user.read().unwrap().has_untranslated(q) }
+
+    fn translate_text(&self, q: &str, from: &str, to: &str) -> Result<String> {
+        self.refresh()?;
+        translate_single(self, &self.user.read().unwrap(), q, from, to)
+    }
+}
+
+// 配置了 refresh 时使用临时密钥：每次请求之前读取，读取失败时不发送请求
+#[cfg(unix)]
+#[test]
+fn sts_refresh() {
+    use super::tests::{StandIn, NO_RETRY};
+    let stand_in =
+        StandIn::new(vec![r#"{"Response":{"RequestId":"x","Source":"en","Target":"zh","TargetTextList":["你好"]}}"#.into()]);
+    let sts = |script: &str| {
+        let user = User { endpoint: Some(stand_in.url.clone()),
+                          retry: NO_RETRY.clone(),
+                          ..User::default() };
+        let refresh = Refresh::Command(vec!["sh".into(), "-c".into(), script.into()]);
+        Sts::new(user, refresh)
+    };
+
+    let translator = sts(r#"echo '{"TmpSecretId":"tmp-id","TmpSecretKey":"tmp-key","Token":"tmp-token","ExpiredTime":4102444800}'"#);
+    assert_eq!(translator.translate_batch(&["hello"], "en", "zh").unwrap(), ["你好"]);
+    let request = stand_in.recv();
+    assert_eq!(request.header("x-tc-token"), Some("tmp-token"));
+    assert_eq!(request.header("x-tc-action"), Some("TextTranslateBatch"));
+    assert!(request.header("authorization").unwrap().contains("Credential=tmp-id/"));
+    assert_eq!(request.json()["SourceTextList"], serde_json::json!(["hello"]));
+    // 读取临时密钥之后，限流的帐号标识不随 SecretId 变化
+    assert_eq!(translator.account(), "tencent:sts");
+
+    let translator = sts("echo expired >&2; exit 3");
+    let expected = "无法读取腾讯云的临时密钥: 外部程序 `sh` 异常退出：exit status: 3\nexpired\n";
+    let err = translator.translate_batch(&["hi"], "en", "zh").unwrap_err();
+    assert_eq!(format!("{err:#}"), expected);
+    let err = translator.detect("hi").unwrap_err();
+    assert_eq!(format!("{err:#}"), expected);
+}
+
+// 签名过期时按照响应的 Date 校正时间并重新签名：偏移量只属于这个帐号
+#[test]
+fn signature_expire() {
+    use super::tests::{StandIn, NO_RETRY};
+    let expire = r#"{"Response":{"Error":{"Code":"AuthFailure.SignatureExpire","Message":"签名过期"},"RequestId":"1"}}"#;
+    let success =
+        r#"{"Response":{"RequestId":"2","Source":"en","Target":"zh","TargetTextList":["你好"]}}"#;
+    let stand_in = StandIn::with_headers(vec![("date: Mon, 25 Feb 2019 08:44:25 GMT\r\n".into(),
+                                               expire.into()),
+                                              (String::new(), success.into())]);
+    let user = User { endpoint: Some(stand_in.url.clone()),
+                      retry: NO_RETRY.clone(),
+                      ..User::default() };
+    assert_eq!(user.translate_batch(&["hello"], "en", "zh").unwrap(), ["你好"]);
+    let timestamp = |request: super::tests::Request| -> i64 {
+        request.header("x-tc-timestamp").unwrap().parse().unwrap()
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    assert!((timestamp(stand_in.recv()) - now).abs() <= 1);
+    assert!((1551084265..=1551084267).contains(&timestamp(stand_in.recv())));
+    assert!(user.offset.load(Ordering::Relaxed) < 0);
+    assert_eq!(User::default().offset.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,171 @@
+//! 翻译接口共用的测试替身：不调用翻译 API 的 [`Upper`]，以及记录请求的本地替身服务
+//! [`StandIn`]。
+use super::Translator;
+use crate::md::Md;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    sync::mpsc::Receiver,
+};
+use translation_api_cn::{Limit, Retry};
+
+pub static NO_RETRY: Retry = Retry { attempts:    1,
+                                     backoff:     0,
+                                     max_backoff: 0,
+                                     jitter:      false, };
+
+// 标题、行内代码和表格
+pub static SAMPLE: &str = "# Title\n\nHello, `world`!\n\n| a | b |\n|---|---|\n| c | d |";
+
+// 不调用翻译 API：每段译文为原文的大写形式
+#[derive(Debug)]
+pub struct Upper(pub Limit);
+
+impl Translator for Upper {
+    fn limit(&self) -> &Limit { &self.0 }
+
+    fn account(&self) -> String { "upper".into() }
+
+    fn qps(&self) -> u8 { 0 }
+
+    fn retry(&self) -> &Retry { &NO_RETRY }
+
+    fn translate(&self, q: &[&str], _: &str, _: &str) -> anyhow::Result<Vec<String>> {
+        Ok(q.iter().map(|s| s.to_uppercase()).collect())
+    }
+}
+
+// 替身服务收到的一个请求
+#[derive(Debug)]
+pub struct Request {
+    // 请求路径，包括查询参数
+    pub path:    String,
+    // 请求头，名称为小写
+    pub headers: Vec<(String, String)>,
+    pub body:    String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    // 以表单方式提交的请求体中，名为 `key` 的所有值
+    pub fn form(&self, key: &str) -> Vec<String> {
+        reqwest::Url::parse(&format!("http://stand-in/?{}", self.body)).unwrap()
+                                                                       .query_pairs()
+                                                                       .filter(|(k, _)| k == key)
+                                                                       .map(|(_, v)| v.into())
+                                                                       .collect()
+    }
+
+    pub fn json(&self) -> serde_json::Value { serde_json::from_str(&self.body).unwrap() }
+}
+
+// 本地的替身服务：按顺序对每个请求返回一个 JSON 响应体，并转发收到的请求
+pub struct StandIn {
+    pub url:  String,
+    requests: Receiver<Request>,
+}
+
+impl StandIn {
+    pub fn new(replies: Vec<String>) -> Self {
+        Self::with_headers(replies.into_iter().map(|reply| (String::new(), reply)).collect())
+    }
+
+    // 每个响应附带额外的响应头：以 `\r\n` 结尾的若干行
+    pub fn with_headers(replies: Vec<(String, String)>) -> Self {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, requests) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for ((extra, reply), stream) in replies.into_iter().zip(listener.incoming()) {
+                let mut stream = BufReader::new(stream.unwrap());
+                let mut line = String::new();
+                stream.read_line(&mut line).unwrap();
+                let path = line.split(' ').nth(1).unwrap_or_default().to_owned();
+                let mut headers = Vec::new();
+                line.clear();
+                while stream.read_line(&mut line).unwrap() > 2 {
+                    if let Some((k, v)) = line.split_once(':') {
+                        headers.push((k.to_ascii_lowercase(), v.trim().to_owned()));
+                    }
+                    line.clear();
+                }
+                let len =
+                    headers.iter()
+                           .find(|(k, _)| k == "content-length")
+                           .map_or(0, |(_, v)| v.parse().unwrap());
+                let mut body = vec![0; len];
+                stream.read_exact(&mut body).unwrap();
+                let body = String::from_utf8(body).unwrap();
+                tx.send(Request { path, headers, body }).unwrap();
+                write!(stream.get_mut(),
+                       "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n{}content-length: \
+                        {}\r\nconnection: close\r\n\r\n{}",
+                       extra,
+                       reply.len(),
+                       reply).unwrap();
+            }
+        });
+        Self { url, requests }
+    }
+
+    // 按顺序取出收到的请求
+    pub fn recv(&self) -> Request { self.requests.recv().unwrap() }
+}
+
+#[test]
+fn translator_double() {
+    let output = |limit| Upper(limit).translate_md(Md::new(SAMPLE), "en", "zh").unwrap();
+    let md = output(Limit::Byte(0));
+    assert_eq!(md, output(Limit::Byte(10)));
+    assert_eq!(md, output(Limit::Char(10)));
+    insta::assert_display_snapshot!(md, @r###"
+    # Title
+
+    # TITLE
+
+    Hello, `world`!
+
+    HELLO, `WORLD`!
+
+    |a	A|b	B|
+    |---|---|
+    |c	C|d	D|
+    "###);
+}
+
+// 第 2 次请求遇到暂时性的错误：只重新发送这一次请求的段落
+#[test]
+fn retry_one_chunk() {
+    use std::sync::Mutex;
+    #[derive(Debug)]
+    struct Flaky {
+        retry: Retry,
+        sent:  Mutex<Vec<Vec<String>>>,
+    }
+    impl Translator for Flaky {
+        fn limit(&self) -> &Limit { &Limit::Char(0) }
+
+        fn account(&self) -> String { "flaky".into() }
+
+        fn qps(&self) -> u8 { 0 }
+
+        fn retry(&self) -> &Retry { &self.retry }
+
+        fn is_retriable(&self, _: &anyhow::Error) -> bool { true }
+
+        fn translate(&self, q: &[&str], _: &str, _: &str) -> anyhow::Result<Vec<String>> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(q.iter().map(|s| s.to_string()).collect());
+            anyhow::ensure!(sent.len() != 2, "timeout");
+            Ok(q.iter().map(|s| s.to_uppercase()).collect())
+        }
+    }
+    let flaky = Flaky { retry: Retry { attempts: 2, ..NO_RETRY.clone() },
+                        sent:  Mutex::default(), };
+    let output =
+        super::translate_chunks(&flaky, &["a", "b", "c"], 2, |q| flaky.translate(q, "en", "zh"));
+    assert_eq!(output.unwrap(), ["A", "B", "C"]);
+    assert_eq!(*flaky.sent.lock().unwrap(), [vec!["a", "b"], vec!["c"], vec!["c"]]);
+}
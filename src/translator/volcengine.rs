@@ -0,0 +1,65 @@
+use super::{translate_chunks, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use reqwest::blocking::{self, Client};
+use serde_json::from_slice;
+use translation_api_cn::{
+    language::Provider,
+    volcengine::{Header, Query, Response, ResponseError, User, MAX_TEXT_LIST},
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("volcengine:{}", self.id) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<ResponseError>()
+           .map_or_else(|| is_network_error(err), ResponseError::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Volcengine.pair(from, to)?;
+        Ok(())
+    }
+
+    /// 单次请求的 `TextList` 最多 [`MAX_TEXT_LIST`] 条，由 [`translate_batch`] 拆分。
+    ///
+    /// [`translate_batch`]: Translator::translate_batch
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Volcengine.pair(from, to)?;
+        let query = Query::new(q, from, to);
+        let mut header = Header::new(self, &query)?;
+        let bytes = send(&mut header)?.bytes()?;
+        let response = from_slice::<Response>(&bytes)?;
+        debug!("\nquery = {:#?}\nheader = {:#?}\nbytes = {:?}\nresponse = {:#?}",
+               query, header, bytes, response);
+        Ok(response.dst_owned()?)
+    }
+
+    fn translate_batch(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        translate_chunks(self, q, MAX_TEXT_LIST, |q| self.translate(q, from, to))
+    }
+}
+
+#[rustfmt::skip]
+fn send(header: &mut Header) -> Result<blocking::Response> {
+    header.authorization()?; // 更改 query 或者 user 时必须重新生成验证信息
+    let map = {
+        use reqwest::header::{HeaderName, HeaderValue};
+        use std::str::FromStr;
+        header.header()
+              .into_iter()
+              .filter_map(|(k, v)| match (HeaderName::from_str(k), HeaderValue::from_str(v)) {
+                  (Ok(key), Ok(value)) => Some((key, value)),
+                  _ => None,
+              })
+              .collect()
+    };
+    Client::new().post(header.url()).headers(map).body(header.body.clone()).send().map_err(|e| e.into())
+}
@@ -0,0 +1,68 @@
+use super::{send, Translator};
+use crate::retry::is_network_error;
+use anyhow::{Error, Result};
+use serde_json::from_slice;
+use translation_api_cn::{
+    language::Provider,
+    youdao::{self, Query, Response, User},
+    Limit, Retry,
+};
+
+impl Translator for User {
+    fn limit(&self) -> &Limit { &self.limit }
+
+    fn account(&self) -> String { format!("youdao:{}", self.appkey) }
+
+    fn qps(&self) -> u8 { self.qps }
+
+    fn retry(&self) -> &Retry { &self.retry }
+
+    fn is_retriable(&self, err: &Error) -> bool {
+        err.downcast_ref::<youdao::Error>()
+           .map_or_else(|| is_network_error(err), youdao::Error::is_retriable)
+    }
+
+    fn check_languages(&self, from: &str, to: &str) -> Result<()> {
+        Provider::Youdao.pair(from, to)?;
+        Ok(())
+    }
+
+    /// 语言代码转换成有道智云的代码，比如简体中文 `zh` 转换成 `zh-CHS`。
+    fn translate(&self, q: &[&str], from: &str, to: &str) -> Result<Vec<String>> {
+        let (from, to) = Provider::Youdao.pair(from, to)?;
+        let q = q.join("\n");
+        let mut query = Query::new(&q, from, to);
+        let bytes = send(self.url(), &{
+                        let sign = query.sign(self);
+                        debug!("sign = {:#?}", sign);
+                        sign
+                    })?.bytes()?;
+        let response = from_slice::<Response>(&bytes)?;
+        debug!("\nquery = {:#?}\nbytes = {:?}\nresponse = {:#?}", query, bytes, response);
+        Ok(response.dst_owned()?)
+    }
+}
+
+#[test]
+fn translate_via_endpoint() {
+    use super::tests::StandIn;
+    let stand_in = StandIn::new(vec![r#"{"errorCode":"0","translation":["你好\n世界"],"l":"en2zh-CHS"}"#.into(),
+                                     r#"{"errorCode":"411","l":"en2zh-CHS"}"#.into()]);
+    let user = User { appkey: "id".into(),
+                      key: "key".into(),
+                      endpoint: Some(stand_in.url.clone()),
+                      ..User::default() };
+    assert_eq!(user.translate(&["hello", "world"], "en", "zh").unwrap(), ["你好", "世界"]);
+    let request = stand_in.recv();
+    assert_eq!(request.form("q"), ["hello\nworld"]);
+    assert_eq!(request.form("to"), ["zh-CHS"]);
+    assert_eq!(request.form("appKey"), ["id"]);
+    assert_eq!(request.form("signType"), ["v3"]);
+    let mut query = Query::new("hello\nworld", "en", "zh-CHS");
+    query.curtime = request.form("curtime").remove(0);
+    query.salt = request.form("salt").remove(0);
+    assert_eq!(request.form("sign"), [query.sign(&user).sign]);
+
+    let err = user.translate(&["hello"], "en", "zh").unwrap_err();
+    assert!(user.is_retriable(&err), "{err}");
+}
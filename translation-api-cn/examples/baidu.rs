@@ -186,9 +186,10 @@ impl Cmd {
     ///       这会导致查询字符串和身份验证信息（appid 和 key）不变时，计算的 MD5 （sign）不变。
     fn to_user(&self) -> User {
         User { appid: self.appid.clone(),
-               key:   self.key.clone(),
-               qps:   1,
-               salt:  "0".into(),
-               limit: Limit::Byte(6000), }
+               key: self.key.clone(),
+               qps: 1,
+               salt: "0".into(),
+               limit: Limit::Byte(6000),
+               ..User::default() }
     }
 }
@@ -0,0 +1,334 @@
+use crate::{Limit, Retry};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use sha1::Sha1;
+use std::{borrow::Cow, fmt::Write};
+use time::OffsetDateTime;
+
+/// 接口名称
+pub const ACTION: &str = "TranslateGeneral";
+/// 接口版本
+pub const VERSION: &str = "2018-10-12";
+
+/// HMAC-SHA1 算法
+pub type HmacSha1 = Hmac<Sha1>;
+
+/// 翻译前的必要信息
+///
+/// 参考：https://help.aliyun.com/document_detail/158244.html
+#[derive(Debug)]
+pub struct Query<'q> {
+    /// 请求翻译 query，必须为 UTF-8 编码。多个段落以 `\n` 分隔。
+    pub q:         &'q str,
+    /// 翻译源语言，可设置为 auto
+    pub from:      &'q str,
+    /// 翻译目标语言
+    pub to:        &'q str,
+    /// 请求的时间戳：ISO 8601 格式的 UTC 时间，比如 `2016-02-23T12:46:24Z`
+    pub timestamp: String,
+    /// 唯一随机数，用于防止重放攻击。不同请求应使用不同的随机数。
+    pub nonce:     String,
+}
+
+/// 账户信息
+/// 需要：机器翻译（alimt）的访问权限
+#[derive(Debug, Deserialize)]
+#[serde(rename = "alibaba")] // for config or cmd
+pub struct User {
+    /// AccessKeyId
+    pub id:       String,
+    /// AccessKeySecret，这个字段用于生成签名，不用于直接构造请求内容
+    pub key:      String,
+    /// 地域，默认为华东1（杭州）：cn-hangzhou。
+    #[serde(default = "default_region")]
+    pub region:   String,
+    /// 翻译场景：通用版本为 general，默认为 general。
+    #[serde(default = "default_scene")]
+    pub scene:    String,
+    /// 每秒请求数（QPS），默认为 50。
+    #[serde(default = "default_qps")]
+    pub qps:      u8,
+    /// 每次请求的字符上限，默认为 Char(5000)。
+    #[serde(default = "default_limit")]
+    pub limit:    Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:    Retry,
+    /// 请求地址，默认为 `https://mt.<region>.aliyuncs.com`。
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+fn default_region() -> String { String::from("cn-hangzhou") }
+fn default_scene() -> String { String::from("general") }
+fn default_qps() -> u8 { 50 }
+fn default_limit() -> Limit { Limit::Char(5000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { id:       String::new(),
+               key:      String::new(),
+               region:   default_region(),
+               scene:    default_scene(),
+               qps:      default_qps(),
+               limit:    default_limit(),
+               retry:    Retry::default(),
+               endpoint: None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则根据 `region` 生成。
+    pub fn url(&self) -> Cow<'_, str> {
+        match self.endpoint.as_deref() {
+            Some(endpoint) => endpoint.into(),
+            None => format!("https://mt.{}.aliyuncs.com", self.region).into(),
+        }
+    }
+}
+
+impl<'q> Query<'q> {
+    /// 实例化：`timestamp` 为当前时间，`nonce` 为当前时间的纳秒数。
+    pub fn new(q: &'q str, from: &'q str, to: &'q str) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self { q,
+               from,
+               to,
+               timestamp: timestamp(now),
+               nonce: now.unix_timestamp_nanos().to_string() }
+    }
+
+    /// 计算 HMAC-SHA1 签名，返回以表单方式提交的数据。
+    /// 当 User 或 Query 的任何内容发生变动时，必须重新调用此方法。
+    pub fn sign<'f>(&'f self, user: &'f User) -> Form<'f> {
+        let mut params = vec![("AccessKeyId", user.id.as_str()),
+                              ("Action", ACTION),
+                              ("Format", "JSON"),
+                              ("FormatType", "text"),
+                              ("RegionId", &user.region),
+                              ("Scene", &user.scene),
+                              ("SignatureMethod", "HMAC-SHA1"),
+                              ("SignatureNonce", &self.nonce),
+                              ("SignatureVersion", "1.0"),
+                              ("SourceLanguage", self.from),
+                              ("SourceText", self.q),
+                              ("TargetLanguage", self.to),
+                              ("Timestamp", &self.timestamp),
+                              ("Version", VERSION)];
+        let signature = signature("POST", &mut params, &user.key);
+        Form { params, signature }
+    }
+}
+
+/// ISO 8601 格式的 UTC 时间，精确到秒。
+pub fn timestamp(t: OffsetDateTime) -> String {
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            t.year(),
+            u8::from(t.month()),
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second())
+}
+
+/// RFC 3986 规定的百分号编码：除了 `A-Z a-z 0-9 - _ . ~` 之外的字节都编码成 `%XY`。
+pub fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => write!(encoded, "%{b:02X}").unwrap(),
+        }
+    }
+    encoded
+}
+
+/// RPC 风格的签名：
+/// 1. 参数按名称排序，名称和值各自编码后以 `=` 连接，参数之间以 `&` 连接；
+/// 2. StringToSign = `HTTPMethod&%2F&` + 编码后的第 1 步结果；
+/// 3. 以 `AccessKeySecret&` 为密钥计算 StringToSign 的 HMAC-SHA1 值，再进行 Base64 编码。
+///
+/// 参考：https://help.aliyun.com/document_detail/315526.html
+pub fn signature(method: &str, params: &mut [(&str, &str)], secret: &str) -> String {
+    params.sort_unstable_by_key(|&(k, _)| k);
+    let canonicalized = params.iter()
+                              .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+                              .collect::<Vec<_>>()
+                              .join("&");
+    let string_to_sign =
+        format!("{method}&{}&{}", percent_encode("/"), percent_encode(&canonicalized));
+    let mut mac = HmacSha1::new_from_slice(format!("{secret}&").as_bytes())
+        .expect("HMAC 可以接受任意长度的密钥");
+    mac.update(string_to_sign.as_bytes());
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// 以表单方式提交的数据：已排序的公共参数、接口参数以及签名
+#[derive(Debug)]
+pub struct Form<'f> {
+    pub params:    Vec<(&'f str, &'f str)>,
+    pub signature: String,
+}
+
+impl<'f> Serialize for Form<'f> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.params.len() + 1))?;
+        for (k, v) in &self.params {
+            map.serialize_entry(k, v)?;
+        }
+        map.serialize_entry("Signature", &self.signature)?;
+        map.end()
+    }
+}
+
+/// 响应的信息。要么返回翻译结果，要么返回错误信息。
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Response<'r> {
+    Ok {
+        #[serde(rename = "Data", borrow)]
+        data: Data<'r>,
+    },
+    Err(Error),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data<'r> {
+    /// 译文。原文中的 `\n` 会保留在译文中，需要手动分隔。
+    #[serde(rename = "Translated", borrow)]
+    pub translated: Cow<'r, str>,
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容。
+    pub fn dst(&self) -> Result<impl Iterator<Item = &str>, Error> {
+        match self {
+            Response::Ok { data } => Ok(data.translated.trim_end().split('\n')),
+            Response::Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> Result<Vec<String>, Error> {
+        self.dst().map(|iter| iter.map(String::from).collect())
+    }
+}
+
+/// 错误处理 / 错误码
+///
+/// 机器翻译的错误码为数字，网关（比如签名、流控）的错误码为字符串。
+#[derive(Debug, Clone, Deserialize)]
+pub struct Error {
+    #[serde(rename = "Code", deserialize_with = "code")]
+    pub code: String,
+    #[serde(rename = "Message", default)]
+    pub msg:  String,
+}
+
+fn code<'de, D: serde::Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Code {
+        Num(i64),
+        Str(String),
+    }
+    Ok(match Code::deserialize(d)? {
+        Code::Num(n) => n.to_string(),
+        Code::Str(s) => s,
+    })
+}
+
+impl std::error::Error for Error {}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,
+               "错误码：`{}`\n错误信息：`{}`\n错误含义：{}\n以上内容由阿里云机器翻译 API 返回",
+               self.code,
+               self.msg,
+               self.solution())
+    }
+}
+
+impl Error {
+    /// 参考：[错误码列表](https://help.aliyun.com/document_detail/158251.html)
+    pub fn solution(&self) -> &str {
+        match self.code.as_bytes() {
+            b"10001" => "请求超时。\n解决方法：请重试。",
+            b"10002" => "系统错误。\n解决方法：请重试。",
+            b"10003" => "原文解码失败。\n解决方法：请检查原文是否为 UTF-8 编码。",
+            b"10004" => "参数缺失。\n解决方法：请检查是否少传参数。",
+            b"10005" => "语项不支持。\n解决方法：请检查源语言和目标语言是否在语言列表里。",
+            b"10006" => "语种识别失败。\n解决方法：请指明源语言。",
+            b"10007" => "翻译失败。",
+            b"10008" => "字符长度过长。\n解决方法：请减小 limit。",
+            b"10009" => "子账号没有权限。\n解决方法：请在 RAM 控制台为子账号授予机器翻译的权限。",
+            b"10010" => "账号没有开通服务。\n解决方法：请在控制台开通机器翻译服务。",
+            b"10011" => "子账号服务失败。",
+            b"10012" => "翻译服务调用失败。",
+            b"10013" => "账号服务没有开通或者欠费。\n解决方法：请进行账户充值。",
+            b"19999" => "未知异常。",
+            b"SignatureDoesNotMatch" => {
+                "签名错误。\n解决方法：请检查 AccessKeyId 和 AccessKeySecret 是否正确。"
+            }
+            b"InvalidAccessKeyId.NotFound" => "AccessKeyId 不存在。",
+            b"InvalidTimeStamp.Expired" => {
+                "时间戳过期。\n解决方法：请检查本地时间是否和标准时间同步。"
+            }
+            b"SignatureNonceUsed" => "重放请求：SignatureNonce 已被使用。",
+            b"Throttling" | b"Throttling.User" | b"Throttling.Api" => {
+                "访问频率受限。\n解决方法：请降低您的调用频率。"
+            }
+            b"ServiceUnavailable" | b"InternalError" => "服务端的其它异常。\n解决方法：请重试。",
+            _ => "未知错误。",
+        }
+    }
+
+    /// 是否为暂时性的错误：请求超时、服务端异常或者访问频率受限。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool {
+        matches!(self.code.as_bytes(),
+                 b"10001" | b"10002" | b"ServiceUnavailable" | b"InternalError")
+        || self.code.starts_with("Throttling")
+    }
+}
+
+#[test]
+fn signature_test() {
+    // 阿里云 RPC 签名文档中的例子
+    let mut params = [("AccessKeyId", "testid"),
+                      ("Action", "DescribeRegions"),
+                      ("Format", "XML"),
+                      ("SignatureMethod", "HMAC-SHA1"),
+                      ("SignatureNonce", "3ee8c1b8-83d3-44af-a94f-4e0ad82fd6cf"),
+                      ("SignatureVersion", "1.0"),
+                      ("Timestamp", "2016-02-23T12:46:24Z"),
+                      ("Version", "2014-05-26")];
+    params.reverse();
+    assert_eq!(signature("GET", &mut params, "testsecret"), "OLeaidS1JvxuMvnyHOwuJ+uX5qY=");
+    assert_eq!(params[0].0, "AccessKeyId");
+
+    assert_eq!(percent_encode("a b*~/你"), "a%20b%2A~%2F%E4%BD%A0");
+    assert_eq!(timestamp(OffsetDateTime::from_unix_timestamp(1456231584).unwrap()),
+               "2016-02-23T12:46:24Z");
+}
+
+#[test]
+fn response_test() {
+    let success =
+        r#"{"RequestId":"86C9A40A","Data":{"WordCount":"8","Translated":"嗨\n那里"},"Code":"200"}"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst().unwrap().collect::<Vec<_>>(), ["嗨", "那里"]);
+
+    let error = r#"{"RequestId":"86C9A40A","Message":"Request was denied due to user flow control.","Code":"Throttling.User"}"#;
+    let res: Response = serde_json::from_str(error).unwrap();
+    let error = res.dst_owned().unwrap_err();
+    assert_eq!(error.code, "Throttling.User");
+    assert!(error.is_retriable());
+
+    let error = r#"{"RequestId":"86C9A40A","Message":"language pair not supported","Code":10005}"#;
+    let error = serde_json::from_str::<Response>(error).unwrap().dst_owned().unwrap_err();
+    assert_eq!(error.code, "10005");
+    assert!(!error.is_retriable());
+}
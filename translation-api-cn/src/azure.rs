@@ -0,0 +1,221 @@
+use crate::{Limit, Retry};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+pub const URL: &str = "https://api.cognitive.microsofttranslator.com/translate";
+pub const API_VERSION: &str = "3.0";
+
+/// 单次请求中数组的最大元素个数
+pub const MAX_TEXTS: usize = 100;
+
+/// 翻译前的必要信息：语言等参数放在查询字符串中，原文以 JSON 数组提交。
+///
+/// 参考：https://learn.microsoft.com/azure/ai-services/translator/reference/v3-0-translate
+#[derive(Debug)]
+pub struct Query<'q> {
+    /// 请求翻译 query，最多 [`MAX_TEXTS`] 个元素，总计不超过 50000 个字符。
+    pub q:    &'q [&'q str],
+    /// 翻译源语言，为 auto 时不传这个参数，由 API 自动检测。
+    pub from: &'q str,
+    /// 翻译目标语言，注意简体中文为 `zh-Hans`。
+    pub to:   &'q str,
+}
+
+impl<'q> Query<'q> {
+    #[rustfmt::skip]
+    pub fn new(q: &'q [&'q str], from: &'q str, to: &'q str) -> Self { Self { q, from, to } }
+
+    /// 查询字符串中的参数
+    pub fn params(&self, user: &'q User) -> Vec<(&'q str, &'q str)> {
+        let mut params = vec![("api-version", API_VERSION), ("to", self.to)];
+        if !self.from.is_empty() && self.from != "auto" {
+            params.push(("from", self.from));
+        }
+        params.push(("textType", &user.text_type));
+        params
+    }
+
+    /// JSON 格式的请求体：`[{"Text": "..."}, ...]`
+    pub fn body(&self) -> Vec<Text<'q>> { self.q.iter().map(|&text| Text { text }).collect() }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Text<'t> {
+    #[serde(rename = "Text")]
+    pub text: &'t str,
+}
+
+/// 账户信息
+#[derive(Debug, Deserialize)]
+#[serde(rename = "azure")] // for config or cmd
+pub struct User {
+    /// 资源密钥，以 `Ocp-Apim-Subscription-Key` 请求头提交
+    pub key:       String,
+    /// 资源所在的地域，以 `Ocp-Apim-Subscription-Region` 请求头提交。
+    /// 使用全局（global）资源时可以不设置。
+    #[serde(default)]
+    pub region:    Option<String>,
+    /// 原文类型：plain | html，默认为 plain。
+    #[serde(default = "default_text_type")]
+    pub text_type: String,
+    /// 每秒请求数（QPS），默认为 10。
+    #[serde(default = "default_qps")]
+    pub qps:       u8,
+    /// 每次请求的字符上限，默认为 Char(50000)。
+    #[serde(default = "default_limit")]
+    pub limit:     Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:     Retry,
+    /// 请求地址，默认为 [`URL`]。
+    #[serde(default)]
+    pub endpoint:  Option<String>,
+}
+
+fn default_text_type() -> String { String::from("plain") }
+fn default_qps() -> u8 { 10 }
+fn default_limit() -> Limit { Limit::Char(50000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { key:       String::new(),
+               region:    None,
+               text_type: default_text_type(),
+               qps:       default_qps(),
+               limit:     default_limit(),
+               retry:     Retry::default(),
+               endpoint:  None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则为 [`URL`]。
+    pub fn url(&self) -> &str { self.endpoint.as_deref().unwrap_or(URL) }
+
+    /// 认证所需的请求头
+    pub fn header(&self) -> Vec<(&str, &str)> {
+        let mut header = vec![("Ocp-Apim-Subscription-Key", self.key.as_str())];
+        if let Some(region) = &self.region {
+            header.push(("Ocp-Apim-Subscription-Region", region));
+        }
+        header
+    }
+}
+
+/// 响应的信息。要么返回翻译结果，要么返回错误信息。
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Response<'r> {
+    /// 与请求的数组一一对应
+    #[serde(borrow)]
+    Ok(Vec<Item<'r>>),
+    Err {
+        error: Error,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Item<'r> {
+    /// 每个目标语言一个译文；这里只请求一个目标语言。
+    #[serde(borrow)]
+    pub translations: Vec<Translation<'r>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Translation<'r> {
+    #[serde(borrow)]
+    pub text: Cow<'r, str>,
+    pub to:   &'r str,
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容。
+    pub fn dst(&self) -> Result<impl Iterator<Item = &str>, Error> {
+        match self {
+            Response::Ok(items) => {
+                Ok(items.iter().flat_map(|i| i.translations.first()).map(|t| t.text.as_ref()))
+            }
+            Response::Err { error } => Err(error.clone()),
+        }
+    }
+
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> Result<Vec<String>, Error> {
+        self.dst().map(|iter| iter.map(String::from).collect())
+    }
+}
+
+/// 错误处理 / 错误码
+#[derive(Debug, Clone, Deserialize)]
+pub struct Error {
+    pub code: u32,
+    #[serde(rename = "message")]
+    pub msg:  String,
+}
+
+impl std::error::Error for Error {}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,
+               "错误码：`{}`\n错误信息：`{}`\n错误含义：{}\n以上内容由 Azure 翻译 API 返回",
+               self.code,
+               self.msg,
+               self.solution())
+    }
+}
+
+impl Error {
+    /// 参考：[错误码列表](https://learn.microsoft.com/azure/ai-services/translator/reference/v3-0-reference#errors)
+    pub fn solution(&self) -> &str {
+        match self.code {
+            400000 => "某个请求输入无效。",
+            400035 => "源语言无效。\n解决方法：请检查语言是否在语言列表里。",
+            400036 => "目标语言无效。\n解决方法：请检查语言是否在语言列表里，简体中文为 zh-Hans。",
+            400050 => "输入文本过长。\n解决方法：请减小 limit。",
+            400064 => "textType 参数无效。\n解决方法：text_type 只能为 plain 或者 html。",
+            400077 => "超出最大请求大小。\n解决方法：请减小 limit。",
+            401000 => "凭据缺失或无效。\n解决方法：请检查 key 和 region 是否正确。",
+            401015 => "所提供的凭据适用于语音 API，而不是文本翻译。",
+            403000 => "不允许执行该操作。",
+            403001 => "免费层的配额已用完。\n解决方法：请升级定价层，或者等待额度重置。",
+            405000 => "请求方法不受支持。",
+            408001 => "正在准备所请求的翻译系统。\n解决方法：请几分钟后重试。",
+            408002 => "等待传入流时请求超时。\n解决方法：请重试。",
+            415000 => "Content-Type 标头缺失或无效。",
+            429000..=429002 => "请求过于频繁。\n解决方法：请降低您的调用频率。",
+            500000 => "发生意外错误。\n解决方法：请重试。",
+            503000 => "服务暂时不可用。\n解决方法：请重试。",
+            _ => "未知错误。",
+        }
+    }
+
+    /// 是否为暂时性的错误：请求过于频繁、超时或者服务端异常。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool { matches!(self.code / 1000, 408 | 429 | 500 | 503) }
+}
+
+#[test]
+fn response_test() {
+    let user = User { region: Some("eastasia".into()), ..User::default() };
+    let query = Query::new(&["hi", "there"], "auto", "zh-Hans");
+    assert_eq!(query.params(&user), [("api-version", "3.0"),
+                                     ("to", "zh-Hans"),
+                                     ("textType", "plain")]);
+    assert_eq!(serde_json::to_string(&query.body()).unwrap(),
+               r#"[{"Text":"hi"},{"Text":"there"}]"#);
+    assert_eq!(user.header()[1], ("Ocp-Apim-Subscription-Region", "eastasia"));
+
+    let success = r#"[{"detectedLanguage":{"language":"en","score":1.0},"translations":[{"text":"嗨","to":"zh-Hans"}]},{"translations":[{"text":"那里","to":"zh-Hans"}]}]"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst().unwrap().collect::<Vec<_>>(), ["嗨", "那里"]);
+
+    let error = r#"{"error":{"code":429001,"message":"The server rejected the request because the client has exceeded request limits."}}"#;
+    let error = serde_json::from_str::<Response>(error).unwrap().dst_owned().unwrap_err();
+    assert_eq!(error.code, 429001);
+    assert!(error.is_retriable());
+
+    let error = r#"{"error":{"code":401000,"message":"The request is not authorized because credentials are missing or invalid."}}"#;
+    assert!(!serde_json::from_str::<Response>(error).unwrap()
+                                                    .dst_owned()
+                                                    .unwrap_err()
+                                                    .is_retriable());
+}
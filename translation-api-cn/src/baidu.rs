@@ -1,8 +1,10 @@
-use crate::Limit;
+use crate::{Glossary, Limit, Retry};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 pub const URL: &str = "https://fanyi-api.baidu.com/api/trans/vip/translate";
+/// 领域翻译的请求地址
+pub const FIELD_URL: &str = "https://fanyi-api.baidu.com/api/trans/vip/fieldtranslate";
 
 /// 翻译前的必要信息
 #[derive(Debug)]
@@ -12,16 +14,21 @@ pub struct Query<'q> {
     /// TODO: 在传入之前应该把文字控制在 6000 字节以内（汉字约为 2000 个字符），
     ///       超过 6000 字节要分段请求。
     pub q:    &'q str,
-    /// 翻译源语言，可设置为 auto
+    /// 翻译源语言，可设置为 auto。使用百度翻译的代码，见 [`Language::baidu`]。
     ///
-    /// TODO：变成 Option + enum 类型，None 表示 auto
+    /// [`Language::baidu`]: crate::Language::baidu
     pub from: &'q str,
-    /// 翻译目标语言，不可设置为 auto
+    /// 翻译目标语言，不可设置为 auto。可用 [`Provider::pair`] 检查语言对。
     ///
-    /// TODO：和 `from` 共用 enum 类型，但无需是 Option 类型
+    /// [`Provider::pair`]: crate::language::Provider::pair
     pub to:   &'q str,
-    /// appid+q+salt+密钥的 MD5 值，q 是待查询的原文字符串
+    /// appid+q+salt+密钥的 MD5 值，q 是待查询的原文字符串；领域翻译时为
+    /// appid+q+salt+domain+密钥的 MD5 值
     pub sign: String,
+    /// 是否同时返回词典释义（只对单词或者短语有效），见 [`SrcDst::dict`]。
+    ///
+    /// 只有通用翻译接口（[`URL`]）提供词典：为 true 时签名和表单都忽略 [`User::domain`]。
+    pub dict: bool,
 }
 
 /// 账户信息
@@ -29,20 +36,38 @@ pub struct Query<'q> {
 #[serde(rename = "baidu")] // for config or cmd
 pub struct User {
     /// 用户申请得到的 APP ID
-    pub appid: String,
+    pub appid:    String,
     /// 用户申请得到的密钥，这个字段用于生成 MD5 ，不用于直接构造请求内容
-    pub key:   String,
+    pub key:      String,
     /// 随机的字母或数字的字符串
     #[serde(default = "default_salt")]
-    pub salt:  String,
-    /// TODO: QPS：这涉及并发请求，允许不填，默认为 1。
+    pub salt:     String,
+    /// 每秒请求数（QPS）：允许不填，默认为 1。
     /// 高级版用户可设置为 10。
     #[serde(default = "default_qps")]
-    pub qps:   u8,
+    pub qps:      u8,
     /// 每秒并发请求的限制，默认为 Byte(6000)。
     #[serde(default = "default_limit")]
     // #[serde(skip_deserializing)]
     pub limit: Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:    Retry,
+    /// 请求地址，默认为 [`URL`]；设置了 `domain` 时默认为 [`FIELD_URL`]。
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// 领域翻译的领域，比如 `it`（信息技术）、`finance`（金融财经）、`machinery`（机械制造）、
+    /// `senimed`（生物医药）、`novel`（网络文学）、`academic`（学术论文）、
+    /// `aerospace`（航空航天）、`wiki`（人文社科）、`news`（新闻资讯）、
+    /// `law`（法律法规）、`contract`（合同）。
+    ///
+    /// 设置后使用领域翻译接口，需要在控制台开通该领域；大部分领域只支持中英互译。
+    #[serde(default)]
+    pub domain:   Option<String>,
+    /// 术语：只使用 [`Glossary::custom`]。领域翻译接口不支持自定义术语库，不能与 `domain`
+    /// 同时设置。
+    #[serde(default)]
+    pub glossary: Glossary,
 }
 
 fn default_qps() -> u8 { 1 }
@@ -51,30 +76,62 @@ fn default_limit() -> Limit { Limit::Byte(6000) }
 
 impl Default for User {
     fn default() -> Self {
-        Self { appid: String::new(),
-               key:   String::new(),
-               salt:  default_salt(),
-               qps:   default_qps(),
-               limit: default_limit(), }
+        Self { appid:    String::new(),
+               key:      String::new(),
+               salt:     default_salt(),
+               qps:      default_qps(),
+               limit:    default_limit(),
+               retry:    Retry::default(),
+               endpoint: None,
+               domain:   None,
+               glossary: Glossary::default(), }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则根据是否设置 `domain`，为
+    /// [`FIELD_URL`] 或 [`URL`]。
+    pub fn url(&self) -> &str {
+        match (&self.endpoint, &self.domain) {
+            (Some(endpoint), _) => endpoint,
+            (None, Some(_)) => FIELD_URL,
+            (None, None) => URL,
+        }
+    }
+
+    /// 查词的请求地址：只有通用翻译接口提供词典。配置了 `endpoint` 时，只把其中领域翻译的
+    /// 路径换成通用翻译的路径；否则为 [`URL`]。
+    pub fn dict_url(&self) -> Cow<'_, str> {
+        let url = self.endpoint.as_deref().unwrap_or(URL);
+        match url.strip_suffix("/fieldtranslate") {
+            Some(base) => format!("{base}/translate").into(),
+            None => url.into(),
+        }
     }
 }
 
 impl<'q> Query<'q> {
     /// 实例化
     pub fn new(q: &'q str, from: &'q str, to: &'q str) -> Self {
-        Self { q, from, to, sign: "".into() }
+        Self { q, from, to, sign: "".into(), dict: false }
     }
 
     /// 计算 MD5 值，返回以表单方式提交的数据，用于身份验证/登录。
     /// 当以下内容至少一项发生变动时，必须调用此方法：
-    /// - User: [appid]、[salt]、[key]
+    /// - User: [appid]、[salt]、[key]、[domain]
     /// - Query: [q][`Query::q`]
     ///
     /// [appid]: `User::appid`
     /// [salt]: `User::salt`
     /// [key]: `User::key`
+    /// [domain]: `User::domain`
     pub fn sign<'f>(&'f mut self, user: &'f User) -> Form<'f> {
-        let data = format!("{}{}{}{}", &user.appid, self.q, &user.salt, &user.key);
+        let domain = if self.dict {
+            ""
+        } else {
+            user.domain.as_deref().unwrap_or_default()
+        };
+        let data = format!("{}{}{}{}{}", &user.appid, self.q, &user.salt, domain, &user.key);
         self.sign = format!("{:x}", md5::compute(data));
         Form::from_user_query(user, self)
     }
@@ -83,22 +140,34 @@ impl<'q> Query<'q> {
 /// 以表单方式提交的数据
 #[derive(Debug, Serialize)]
 pub struct Form<'f> {
-    pub q:     &'f str,
-    pub from:  &'f str,
-    pub to:    &'f str,
-    pub appid: &'f str,
-    pub salt:  &'f str,
-    pub sign:  &'f str,
+    pub q:      &'f str,
+    pub from:   &'f str,
+    pub to:     &'f str,
+    pub appid:  &'f str,
+    pub salt:   &'f str,
+    /// 领域翻译的领域，不设置时不提交
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<&'f str>,
+    /// 为 1 时使用自定义术语库，不参与签名。只有通用翻译接口支持，领域翻译接口会忽略它
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<u8>,
+    /// 为 1 时返回词典释义，不参与签名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dict:   Option<u8>,
+    pub sign:   &'f str,
 }
 
 impl<'f> Form<'f> {
     pub fn from_user_query(user: &'f User, query: &'f Query) -> Self {
-        Self { q:     query.q,
-               from:  query.from,
-               to:    query.to,
-               appid: &user.appid,
-               salt:  &user.salt,
-               sign:  &query.sign, }
+        Self { q:      query.q,
+               from:   query.from,
+               to:     query.to,
+               appid:  &user.appid,
+               salt:   &user.salt,
+               domain: user.domain.as_deref().filter(|_| !query.dict),
+               action: user.glossary.custom.then_some(1),
+               dict:   query.dict.then_some(1),
+               sign:   &query.sign, }
     }
 }
 
@@ -177,7 +246,120 @@ impl<'r> Response<'r> {
 pub struct SrcDst<'r> {
     // pub src: Cow<'r, str>,
     #[serde(borrow)]
-    pub dst: Cow<'r, str>,
+    pub dst:  Cow<'r, str>,
+    /// 请求时 `dict=1` 才会返回：词典释义，内容为 JSON 字符串，使用 [`SrcDst::entry`] 解析。
+    #[serde(default)]
+    pub dict: Option<String>,
+}
+
+impl SrcDst<'_> {
+    /// 解析词典释义。未请求、查无此词或者无法解析时返回 `None`。
+    pub fn entry(&self) -> Option<Dict> {
+        #[derive(Deserialize)]
+        struct Raw {
+            word_result: WordResult,
+        }
+        #[derive(Deserialize)]
+        struct WordResult {
+            simple_means: Dict,
+        }
+        let raw: Raw = serde_json::from_str(self.dict.as_deref()?).ok()?;
+        Some(raw.word_result.simple_means).filter(|d| !d.is_empty())
+    }
+}
+
+/// 词典释义（`simple_means`）。英文单词带有英美音标，中文词语带有拼音：
+/// ```text
+/// apple
+/// 英 [ˈæpl]  美 [ˈæpl]
+/// n. 苹果；苹果树；苹果公司
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Dict {
+    #[serde(default)]
+    pub word_name:  String,
+    #[serde(default)]
+    pub symbols:    Vec<Symbol>,
+    /// 所有词性的释义，词性释义为空时使用
+    #[serde(default)]
+    pub word_means: Vec<String>,
+}
+
+/// 一种读音及其释义
+#[derive(Debug, Default, Deserialize)]
+pub struct Symbol {
+    /// 英式音标
+    #[serde(default)]
+    pub ph_en:       String,
+    /// 美式音标
+    #[serde(default)]
+    pub ph_am:       String,
+    /// 拼音
+    #[serde(default)]
+    pub word_symbol: String,
+    #[serde(default)]
+    pub parts:       Vec<Part>,
+}
+
+/// 一种词性及其释义
+#[derive(Debug, Default, Deserialize)]
+pub struct Part {
+    /// 词性，比如 `n.`；中文词语为 `part_name`，比如 `名`
+    #[serde(default, alias = "part_name")]
+    pub part:  String,
+    #[serde(default)]
+    pub means: Vec<Mean>,
+}
+
+/// 释义：英文单词为字符串；中文词语为对象，只取 `word_mean`。
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Mean {
+    Text(String),
+    Word { word_mean: String },
+}
+
+impl Mean {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Mean::Text(s) | Mean::Word { word_mean: s } => s,
+        }
+    }
+}
+
+impl Dict {
+    pub fn is_empty(&self) -> bool {
+        self.word_means.is_empty() && self.symbols.iter().all(|s| s.parts.is_empty())
+    }
+}
+
+impl std::fmt::Display for Dict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.word_name)?;
+        for symbol in &self.symbols {
+            let mut phonetic = Vec::with_capacity(2);
+            if !symbol.ph_en.is_empty() {
+                phonetic.push(format!("英 [{}]", symbol.ph_en));
+            }
+            if !symbol.ph_am.is_empty() {
+                phonetic.push(format!("美 [{}]", symbol.ph_am));
+            }
+            if !symbol.word_symbol.is_empty() {
+                phonetic.push(format!("[{}]", symbol.word_symbol));
+            }
+            if !phonetic.is_empty() {
+                write!(f, "\n{}", phonetic.join("  "))?;
+            }
+            for part in &symbol.parts {
+                let means: Vec<_> = part.means.iter().map(Mean::as_str).collect();
+                write!(f, "\n{} {}", part.part, means.join("；"))?;
+            }
+        }
+        if self.symbols.iter().all(|s| s.parts.is_empty()) {
+            write!(f, "\n{}", self.word_means.join("；"))?;
+        }
+        Ok(())
+    }
 }
 
 /// 错误处理 / 错误码
@@ -220,10 +402,91 @@ impl Error {
                 "客户端 IP 非法。\n解决方法：检查个人资料里填写的 IP \
                  地址是否正确，可前往开发者信息-基本信息修改。"
             }
-            b"58001" => "译文语言方向不支持。\n解决方法：检查译文语言是否在语言列表里。",
+            b"58001" => {
+                "译文语言方向不支持。\n解决方法：检查译文语言是否在语言列表里；\
+                 领域翻译时检查该领域是否支持这个语言方向（大部分领域只支持中英互译）。"
+            }
             b"58002" => "服务当前已关闭。\n解决方法：请前往管理控制台开启服务。",
+            b"58003" => {
+                "此 IP 已被封禁。\n解决方法：同一 IP 当日使用多个 APPID 发送翻译请求，则该 IP \
+                 将被封禁当日请求权限，次日解封。请勿将 APPID 和密钥填写到第三方软件中。"
+            }
+            b"20003" => {
+                "请求内容存在安全风险。\n解决方法：请检查请求文本是否涉及反动、暴力等相关内容。"
+            }
             b"90107" => "认证未通过或未生效。\n解决方法：请前往我的认证查看认证进度。",
             _ => "未知错误。",
         }
     }
+
+    /// 是否为暂时性的错误：请求超时、系统错误或者访问频率受限。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool {
+        matches!(self.code.as_bytes(), b"52001" | b"52002" | b"54003" | b"54005")
+    }
+}
+
+#[test]
+fn sign_test() {
+    // 官方文档中的例子
+    let mut user = User { appid: "2015063000000001".into(),
+                          key: "12345678".into(),
+                          salt: "1435660288".into(),
+                          ..Default::default() };
+    let mut query = Query::new("apple", "en", "zh");
+    assert_eq!(query.sign(&user).sign, "f89f9594663708c1605f3d736d01d2d4");
+    assert_eq!(user.url(), URL);
+
+    user.domain = Some("it".into());
+    let form = query.sign(&user);
+    assert_eq!(form.sign, "f7e8fb0fbda2c45c81694ef2238960aa");
+    assert_eq!(form.domain, Some("it"));
+    assert_eq!(form.action, None);
+    assert_eq!(user.url(), FIELD_URL);
+
+    user.glossary.custom = true;
+    let form = query.sign(&user);
+    assert_eq!(form.sign, "f7e8fb0fbda2c45c81694ef2238960aa");
+    assert_eq!(form.action, Some(1));
+
+    assert_eq!(user.dict_url(), URL);
+    user.endpoint = Some("http://127.0.0.1:8080/api/trans/vip/fieldtranslate".into());
+    assert_eq!(user.dict_url(), "http://127.0.0.1:8080/api/trans/vip/translate");
+    user.endpoint = Some("http://127.0.0.1:8080/baidu".into());
+    assert_eq!(user.dict_url(), "http://127.0.0.1:8080/baidu");
+}
+
+#[test]
+fn dict_test() {
+    // `dict=1` 时的响应：dict 字段为 JSON 字符串（有删减）
+    let bytes = r#"{"from":"en","to":"zh","trans_result":[{"src":"apple","dst":"\u82f9\u679c","dict":"{\"lang\":\"1\",\"word_result\":{\"simple_means\":{\"word_name\":\"apple\",\"from\":\"original\",\"word_means\":[\"\u82f9\u679c\",\"\u82f9\u679c\u6811\"],\"symbols\":[{\"ph_en\":\"\u02c8\u00e6pl\",\"ph_am\":\"\u02c8\u00e6pl\",\"parts\":[{\"part\":\"n.\",\"means\":[\"\u82f9\u679c\",\"\u82f9\u679c\u6811\"]}]}]}}}"}]}"#;
+    let response: Response = serde_json::from_str(bytes).unwrap();
+    let Response::Ok { res, .. } = response else { panic!("{response:?}") };
+    assert_eq!(res[0].dst, "苹果");
+    let entry = res[0].entry().unwrap();
+    assert_eq!(entry.to_string(), "apple\n英 [ˈæpl]  美 [ˈæpl]\nn. 苹果；苹果树");
+
+    // 中译英：拼音和对象形式的释义
+    let dict = r#"{"lang":"0","word_result":{"simple_means":{"word_name":"苹果","symbols":[{"word_symbol":"píng guǒ","parts":[{"part_name":"名","means":[{"text":"apple","part":"n.","word_mean":"apple"}]}]}]}}}"#;
+    let src_dst = SrcDst { dst: "apple".into(), dict: Some(dict.into()) };
+    assert_eq!(src_dst.entry().unwrap().to_string(), "苹果\n[píng guǒ]\n名 apple");
+
+    // 查无此词时 dict 为空字符串
+    let src_dst = SrcDst { dst:  "hello world".into(),
+                           dict: Some(String::new()), };
+    assert!(src_dst.entry().is_none());
+
+    let mut user = User { appid: "2015063000000001".into(),
+                          key: "12345678".into(),
+                          salt: "1435660288".into(),
+                          domain: Some("it".into()),
+                          ..Default::default() };
+    let mut query = Query::new("apple", "en", "zh");
+    query.dict = true;
+    let form = query.sign(&user);
+    assert_eq!(form.dict, Some(1));
+    // 词典只在通用翻译接口中提供：忽略领域
+    assert_eq!(form.domain, None);
+    assert_eq!(form.sign, "f89f9594663708c1605f3d736d01d2d4");
+    user.domain = None;
+    assert_eq!(query.sign(&user).sign, "f89f9594663708c1605f3d736d01d2d4");
 }
@@ -0,0 +1,173 @@
+use crate::{Limit, Retry};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+pub const URL: &str = "https://api.interpreter.caiyunai.com/v1/translator";
+
+/// 彩云小译支持的语言：除了 auto 只能作为源语言之外，其余语言只能与中文互译。
+pub const LANGUAGES: &[&str] = &["zh", "en", "ja", "ko", "es", "fr", "ru", "de"];
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Error {
+    #[error("彩云小译不支持 `{from}` 到 `{to}` 的翻译\n解决方法：只支持中文（zh）与 \
+             en、ja、ko、es、fr、ru、de 之间互译，或者 auto 到 zh")]
+    Unsupported { from: String, to: String },
+    #[error("HTTP 状态码：`{status}`\n错误信息：`{msg}`\n错误含义：{}\n以上内容由彩云小译 API 返回",
+            solution(.status))]
+    Api { status: u16, msg: String },
+}
+
+fn solution(status: &u16) -> &'static str {
+    match status {
+        400 => "请求参数错误。",
+        401 | 403 => "token 无效或者没有访问权限。\n解决方法：请检查 token 是否正确。",
+        429 => "请求过于频繁或者字符额度已用完。\n解决方法：请降低您的调用频率。",
+        500..=599 => "服务端内部错误。\n解决方法：请重试。",
+        _ => "未知错误。",
+    }
+}
+
+impl Error {
+    /// 根据非 2xx 的 HTTP 状态码和响应体构造错误。
+    pub fn new(status: u16, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(default)]
+            message: String,
+        }
+        let msg = serde_json::from_slice::<Body>(body).map(|b| b.message).unwrap_or_default();
+        Error::Api { status, msg }
+    }
+
+    /// 是否为暂时性的错误：请求过于频繁或者服务端异常。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool {
+        matches!(self, Error::Api { status: 429 | 500..=599, .. })
+    }
+}
+
+/// 生成 `trans_type`，比如 `en2zh`；不支持的语言对返回错误。
+///
+/// `from` 和 `to` 为彩云小译的代码：`-f/-t` 的其他代码（比如 `jp`、`kor`）先用
+/// [`Provider::pair`] 转换。
+///
+/// [`Provider::pair`]: crate::language::Provider::pair
+pub fn trans_type(from: &str, to: &str) -> Result<String, Error> {
+    let supported = match (from, to) {
+        ("auto", "zh") => true,
+        ("zh", other) | (other, "zh") => other != "zh" && LANGUAGES.contains(&other),
+        _ => false,
+    };
+    if supported {
+        Ok(format!("{from}2{to}"))
+    } else {
+        Err(Error::Unsupported { from: from.into(), to: to.into() })
+    }
+}
+
+/// 翻译前的必要信息：以 JSON 格式提交。
+///
+/// 参考：https://docs.caiyunapp.com/blog/2018/09/03/lingocloud-api/
+#[derive(Debug, Serialize)]
+pub struct Query<'q> {
+    /// 请求翻译 query：译文数组与之一一对应。
+    pub source:     &'q [&'q str],
+    /// 语言对，比如 `en2zh`，见 [`trans_type`]。
+    pub trans_type: String,
+    pub request_id: &'q str,
+    /// 源语言为 auto 时，由 API 自动检测。
+    pub detect:     bool,
+}
+
+impl<'q> Query<'q> {
+    /// 实例化：不支持的语言对返回错误。
+    pub fn new(q: &'q [&'q str], from: &str, to: &str) -> Result<Self, Error> {
+        Ok(Self { source:     q,
+                  trans_type: trans_type(from, to)?,
+                  request_id: "bilingual",
+                  detect:     from == "auto", })
+    }
+}
+
+/// 账户信息
+#[derive(Debug, Deserialize)]
+#[serde(rename = "caiyun")] // for config or cmd
+pub struct User {
+    /// 令牌，以 `x-authorization: token <token>` 请求头提交
+    pub key:      String,
+    /// 每秒请求数（QPS），默认为 1。
+    #[serde(default = "default_qps")]
+    pub qps:      u8,
+    /// 每次请求的字符上限，默认为 Char(5000)。
+    #[serde(default = "default_limit")]
+    pub limit:    Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:    Retry,
+    /// 请求地址，默认为 [`URL`]。
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+fn default_qps() -> u8 { 1 }
+fn default_limit() -> Limit { Limit::Char(5000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { key:      String::new(),
+               qps:      default_qps(),
+               limit:    default_limit(),
+               retry:    Retry::default(),
+               endpoint: None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则为 [`URL`]。
+    pub fn url(&self) -> &str { self.endpoint.as_deref().unwrap_or(URL) }
+
+    /// `x-authorization` 请求头的值
+    pub fn authorization(&self) -> String { format!("token {}", self.key) }
+}
+
+/// 成功时的响应信息：`target` 与 `source` 按顺序一一对应。
+#[derive(Debug, Deserialize)]
+pub struct Response<'r> {
+    #[serde(borrow)]
+    pub target: Vec<Cow<'r, str>>,
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容。
+    pub fn dst(&self) -> impl Iterator<Item = &str> { self.target.iter().map(|s| s.as_ref()) }
+
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> Vec<String> { self.target.into_iter().map(|s| s.into()).collect() }
+}
+
+#[test]
+fn trans_type_test() {
+    assert_eq!(trans_type("en", "zh").unwrap(), "en2zh");
+    assert_eq!(trans_type("zh", "ja").unwrap(), "zh2ja");
+    assert_eq!(trans_type("ko", "zh").unwrap(), "ko2zh");
+    assert_eq!(trans_type("auto", "zh").unwrap(), "auto2zh");
+    assert!(matches!(trans_type("en", "ja"), Err(Error::Unsupported { .. })));
+    assert!(trans_type("zh", "zh").is_err());
+    assert!(trans_type("auto", "en").is_err());
+    assert!(trans_type("cht", "zh").is_err());
+
+    let query = Query::new(&["hi", "there"], "en", "zh").unwrap();
+    assert_eq!(serde_json::to_string(&query).unwrap(),
+               r#"{"source":["hi","there"],"trans_type":"en2zh","request_id":"bilingual","detect":false}"#);
+}
+
+#[test]
+fn response_test() {
+    let success = r#"{"confidence":0.8,"target":["嗨","那里"],"rc":0}"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst().collect::<Vec<_>>(), ["嗨", "那里"]);
+
+    let error = Error::new(401, br#"{"message":"Invalid token"}"#);
+    assert!(matches!(&error, Error::Api { msg, .. } if msg == "Invalid token"));
+    assert!(!error.is_retriable());
+    assert!(Error::new(503, b"").is_retriable());
+}
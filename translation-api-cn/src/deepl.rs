@@ -0,0 +1,226 @@
+use crate::{Limit, Retry};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+
+/// 免费版 API 的请求地址：密钥以 `:fx` 结尾
+pub const URL_FREE: &str = "https://api-free.deepl.com/v2/translate";
+/// 专业版 API 的请求地址
+pub const URL_PRO: &str = "https://api.deepl.com/v2/translate";
+
+/// 单次请求中 `text` 的最大条数
+pub const MAX_TEXTS: usize = 50;
+
+/// 翻译前的必要信息
+///
+/// 参考：https://www.deepl.com/docs-api/translate-text
+#[derive(Debug)]
+pub struct Query<'q> {
+    /// 请求翻译 query，每个元素作为一个 `text` 字段提交，最多 [`MAX_TEXTS`] 个。
+    pub q:    &'q [&'q str],
+    /// 翻译源语言（大写），为 `None` 时由 API 自动检测。
+    pub from: Option<String>,
+    /// 翻译目标语言（大写），比如 `DE`、`EN-US`、`ZH`。
+    pub to:   String,
+}
+
+impl<'q> Query<'q> {
+    /// 实例化：DeepL 的语言代码为大写；`from` 为 auto 时不指定源语言。
+    pub fn new(q: &'q [&'q str], from: &str, to: &str) -> Self {
+        let from = (!from.is_empty() && from != "auto").then(|| from.to_uppercase());
+        Self { q, from, to: to.to_uppercase() }
+    }
+
+    pub fn form(&'q self, user: &'q User) -> Form<'q> { Form { user, query: self } }
+}
+
+/// 账户信息
+#[derive(Debug, Deserialize)]
+#[serde(rename = "deepl")] // for config or cmd
+pub struct User {
+    /// 认证密钥（auth_key），以 `DeepL-Auth-Key` 请求头提交
+    pub key:                 String,
+    /// 正式程度：default | more | less | prefer_more | prefer_less。
+    /// 只有部分目标语言（比如德语）支持，默认不指定。
+    #[serde(default)]
+    pub formality:           Option<String>,
+    /// 断句方式：0（不断句）| 1（按标点和换行断句）| nonewlines（只按标点断句）。
+    /// 默认为 nonewlines，因为每个 `text` 已经是一个完整的段落。
+    #[serde(default = "default_split_sentences")]
+    pub split_sentences:     String,
+    /// 是否保留原文的格式（比如句首大小写、句末标点），默认为 false。
+    #[serde(default)]
+    pub preserve_formatting: bool,
+    /// 每秒请求数（QPS），默认为 5。
+    #[serde(default = "default_qps")]
+    pub qps:                 u8,
+    /// 每次请求的字节上限，默认为 Byte(120000)：请求体不得超过 128 KiB。
+    #[serde(default = "default_limit")]
+    pub limit:               Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:               Retry,
+    /// 请求地址，默认根据密钥选择 [`URL_FREE`] 或者 [`URL_PRO`]。
+    #[serde(default)]
+    pub endpoint:            Option<String>,
+}
+
+fn default_split_sentences() -> String { String::from("nonewlines") }
+fn default_qps() -> u8 { 5 }
+fn default_limit() -> Limit { Limit::Byte(120000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { key:                 String::new(),
+               formality:           None,
+               split_sentences:     default_split_sentences(),
+               preserve_formatting: false,
+               qps:                 default_qps(),
+               limit:               default_limit(),
+               retry:               Retry::default(),
+               endpoint:            None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`；否则密钥以 `:fx` 结尾时为
+    /// [`URL_FREE`]，不然为 [`URL_PRO`]。
+    pub fn url(&self) -> &str {
+        match self.endpoint.as_deref() {
+            Some(endpoint) => endpoint,
+            None if self.key.ends_with(":fx") => URL_FREE,
+            None => URL_PRO,
+        }
+    }
+
+    /// `Authorization` 请求头的值
+    pub fn authorization(&self) -> String { format!("DeepL-Auth-Key {}", self.key) }
+}
+
+/// 以表单方式提交的数据：每段文本都是一个 `text` 字段。
+#[derive(Debug)]
+pub struct Form<'f> {
+    pub user:  &'f User,
+    pub query: &'f Query<'f>,
+}
+
+impl<'f> Serialize for Form<'f> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (user, query) = (self.user, self.query);
+        let mut map = serializer.serialize_map(None)?;
+        for text in query.q {
+            map.serialize_entry("text", text)?;
+        }
+        if let Some(from) = &query.from {
+            map.serialize_entry("source_lang", from)?;
+        }
+        map.serialize_entry("target_lang", &query.to)?;
+        if let Some(formality) = &user.formality {
+            map.serialize_entry("formality", formality)?;
+        }
+        map.serialize_entry("split_sentences", &user.split_sentences)?;
+        map.serialize_entry("preserve_formatting",
+                            if user.preserve_formatting { "1" } else { "0" })?;
+        map.end()
+    }
+}
+
+/// 成功时的响应信息。出错时 DeepL 通过 HTTP 状态码表明原因，见 [`Error`]。
+#[derive(Debug, Deserialize)]
+pub struct Response<'r> {
+    #[serde(borrow)]
+    pub translations: Vec<Translation<'r>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Translation<'r> {
+    /// 检测到的源语言
+    pub detected_source_language: &'r str,
+    #[serde(borrow)]
+    pub text:                     Cow<'r, str>,
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容。
+    pub fn dst(&self) -> impl Iterator<Item = &str> {
+        self.translations.iter().map(|t| t.text.as_ref())
+    }
+
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> Vec<String> {
+        self.translations.into_iter().map(|t| t.text.into()).collect()
+    }
+}
+
+/// 错误处理 / HTTP 状态码
+#[derive(Debug, Clone)]
+pub struct Error {
+    pub status: u16,
+    /// 响应体中的 `message` 字段，可能为空
+    pub msg:    String,
+}
+
+impl Error {
+    /// 根据非 2xx 的 HTTP 状态码和响应体构造错误。
+    pub fn new(status: u16, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(default)]
+            message: String,
+        }
+        let msg = serde_json::from_slice::<Body>(body).map(|b| b.message).unwrap_or_default();
+        Self { status, msg }
+    }
+
+    /// 参考：[错误码列表](https://www.deepl.com/docs-api/api-access/general-information)
+    pub fn solution(&self) -> &str {
+        match self.status {
+            400 => "请求参数错误。\n解决方法：请检查语言代码和 formality 等参数。",
+            403 => "认证失败。\n解决方法：请检查 key 是否正确，免费版和专业版的密钥不能混用。",
+            404 => "请求的资源不存在。\n解决方法：请检查 endpoint。",
+            413 => "请求体过大。\n解决方法：请减小 limit。",
+            414 => "请求的 URL 过长。",
+            429 => "请求过于频繁。\n解决方法：请降低您的调用频率。",
+            456 => "超出配额：本月的字符额度已用完。\n解决方法：请升级套餐或者等待额度重置。",
+            503 | 529 => "服务暂时不可用。\n解决方法：请重试。",
+            500..=599 => "服务端内部错误。\n解决方法：请重试。",
+            _ => "未知错误。",
+        }
+    }
+
+    /// 是否为暂时性的错误：请求过于频繁或者服务端异常。这些错误可以重试。
+    /// 注意 456（超出配额）不可重试。
+    pub fn is_retriable(&self) -> bool { matches!(self.status, 429 | 500..=599) }
+}
+
+impl std::error::Error for Error {}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,
+               "HTTP 状态码：`{}`\n错误信息：`{}`\n错误含义：{}\n以上内容由 DeepL API 返回",
+               self.status,
+               self.msg,
+               self.solution())
+    }
+}
+
+#[test]
+fn url_test() {
+    let mut user = User { key: "xxx:fx".into(), ..User::default() };
+    assert_eq!(user.url(), URL_FREE);
+    user.key = "xxx".into();
+    assert_eq!(user.url(), URL_PRO);
+    assert_eq!(user.authorization(), "DeepL-Auth-Key xxx");
+}
+
+#[test]
+fn response_test() {
+    let success = r#"{"translations":[{"detected_source_language":"EN","text":"Hallo"},{"detected_source_language":"EN","text":"dort"}]}"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst().collect::<Vec<_>>(), ["Hallo", "dort"]);
+
+    let error = Error::new(456, br#"{"message":"Quota Exceeded"}"#);
+    assert_eq!(error.msg, "Quota Exceeded");
+    assert!(!error.is_retriable());
+    assert!(Error::new(429, b"").is_retriable());
+    assert!(!Error::new(403, b"").is_retriable());
+}
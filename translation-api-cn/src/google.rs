@@ -0,0 +1,241 @@
+use crate::{Limit, Retry};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+
+pub const URL: &str = "https://translation.googleapis.com/language/translate/v2";
+
+/// 单次请求中 `q` 的最大个数
+pub const MAX_TEXTS: usize = 128;
+
+/// 翻译前的必要信息
+///
+/// 参考：https://cloud.google.com/translate/docs/reference/rest/v2/translate
+#[derive(Debug)]
+pub struct Query<'q> {
+    /// 请求翻译 query，每个元素作为一个 `q` 字段提交，最多 [`MAX_TEXTS`] 个。
+    pub q:    &'q [&'q str],
+    /// 翻译源语言，为 auto 时不传这个参数，由 API 自动检测。
+    pub from: &'q str,
+    /// 翻译目标语言
+    pub to:   &'q str,
+}
+
+impl<'q> Query<'q> {
+    #[rustfmt::skip]
+    pub fn new(q: &'q [&'q str], from: &'q str, to: &'q str) -> Self { Self { q, from, to } }
+}
+
+/// 以表单方式提交：`q` 重复出现，`format` 固定为 text。API key 放在查询字符串中，
+/// 见 [`User::key`]。
+impl<'q> Serialize for Query<'q> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for q in self.q {
+            map.serialize_entry("q", q)?;
+        }
+        if !self.from.is_empty() && self.from != "auto" {
+            map.serialize_entry("source", self.from)?;
+        }
+        map.serialize_entry("target", self.to)?;
+        map.serialize_entry("format", "text")?;
+        map.end()
+    }
+}
+
+/// 账户信息
+#[derive(Debug, Deserialize)]
+#[serde(rename = "google")] // for config or cmd
+pub struct User {
+    /// API key，以 `?key=` 查询字符串提交
+    pub key:      String,
+    /// 每秒请求数（QPS），默认为 10。
+    #[serde(default = "default_qps")]
+    pub qps:      u8,
+    /// 每次请求的字符上限，默认为 Char(30000)。
+    #[serde(default = "default_limit")]
+    pub limit:    Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:    Retry,
+    /// 请求地址，默认为 [`URL`]。
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+fn default_qps() -> u8 { 10 }
+fn default_limit() -> Limit { Limit::Char(30000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { key:      String::new(),
+               qps:      default_qps(),
+               limit:    default_limit(),
+               retry:    Retry::default(),
+               endpoint: None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则为 [`URL`]。
+    pub fn url(&self) -> &str { self.endpoint.as_deref().unwrap_or(URL) }
+}
+
+/// 响应的信息。要么返回翻译结果，要么返回错误信息。
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Response<'r> {
+    Ok {
+        #[serde(borrow)]
+        data: Data<'r>,
+    },
+    Err {
+        error: Error,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Data<'r> {
+    #[serde(borrow)]
+    pub translations: Vec<Translation<'r>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Translation<'r> {
+    /// 即使 `format=text`，译文中也可能含有 `&#39;` 之类的 HTML 实体，见 [`unescape`]。
+    #[serde(rename = "translatedText", borrow)]
+    pub text: Cow<'r, str>,
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容：HTML 实体已被还原。
+    pub fn dst(&self) -> Result<impl Iterator<Item = Cow<'_, str>>, Error> {
+        match self {
+            Response::Ok { data } => Ok(data.translations.iter().map(|t| unescape(&t.text))),
+            Response::Err { error } => Err(error.clone()),
+        }
+    }
+
+    /// 提取翻译内容：HTML 实体已被还原。
+    pub fn dst_owned(self) -> Result<Vec<String>, Error> {
+        self.dst().map(|iter| iter.map(Cow::into_owned).collect())
+    }
+}
+
+/// 还原 HTML 实体：具名实体（比如 `&amp;`）以及 `&#39;`、`&#x27;` 这样的数字实体。
+/// 无法识别的实体保持原样。
+pub fn unescape(s: &str) -> Cow<'_, str> {
+    if !s.contains('&') {
+        return s.into();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let c = entity.and_then(|(name, _)| match name {
+                          "amp" => Some('&'),
+                          "lt" => Some('<'),
+                          "gt" => Some('>'),
+                          "quot" => Some('"'),
+                          "apos" => Some('\''),
+                          _ => {
+                              let n = name.strip_prefix('#')?;
+                              let n = match n.strip_prefix(['x', 'X']) {
+                                  Some(hex) => u32::from_str_radix(hex, 16),
+                                  None => n.parse(),
+                              };
+                              char::from_u32(n.ok()?)
+                          }
+                      });
+        match (c, entity) {
+            (Some(c), Some((_, end))) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.into()
+}
+
+/// 错误处理 / 错误码
+#[derive(Debug, Clone, Deserialize)]
+pub struct Error {
+    /// HTTP 状态码
+    pub code:   u16,
+    #[serde(rename = "message")]
+    pub msg:    String,
+    #[serde(default)]
+    pub errors: Vec<Reason>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Reason {
+    pub reason: String,
+}
+
+impl std::error::Error for Error {}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,
+               "错误码：`{}`（{}）\n错误信息：`{}`\n错误含义：{}\n以上内容由 Google 翻译 API 返回",
+               self.code,
+               self.reason(),
+               self.msg,
+               self.solution())
+    }
+}
+
+impl Error {
+    /// 第一个错误原因，比如 `keyInvalid`、`userRateLimitExceeded`
+    pub fn reason(&self) -> &str { self.errors.first().map_or("", |r| r.reason.as_str()) }
+
+    /// 参考：[错误码列表](https://cloud.google.com/translate/troubleshooting)
+    pub fn solution(&self) -> &str {
+        match (self.code, self.reason()) {
+            (_, "keyInvalid") => "API key 无效。\n解决方法：请检查 key 是否正确。",
+            (_, "dailyLimitExceeded") => "超出每日配额。\n解决方法：请在控制台提高配额。",
+            (_, "userRateLimitExceeded" | "rateLimitExceeded") => {
+                "请求过于频繁。\n解决方法：请降低您的调用频率。"
+            }
+            (_, "quotaExceeded") => "超出配额。\n解决方法：请在控制台提高配额。",
+            (_, "accessNotConfigured") => {
+                "项目未启用 Cloud Translation API。\n解决方法：请在控制台启用。"
+            }
+            (400, _) => "请求参数错误。\n解决方法：请检查语言是否在语言列表里。",
+            (403, _) => "没有访问权限。",
+            (429, _) => "请求过于频繁。\n解决方法：请降低您的调用频率。",
+            (500..=599, _) => "服务端内部错误。\n解决方法：请重试。",
+            _ => "未知错误。",
+        }
+    }
+
+    /// 是否为暂时性的错误：请求过于频繁或者服务端异常。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool {
+        matches!(self.code, 429 | 500..=599)
+        || matches!(self.reason(), "userRateLimitExceeded" | "rateLimitExceeded" | "backendError")
+    }
+}
+
+#[test]
+fn response_test() {
+    let success = r#"{"data":{"translations":[{"translatedText":"it&#39;s &quot;ok&quot; &amp; &lt;b&gt;","detectedSourceLanguage":"zh"},{"translatedText":"AT&T &#x4e2d; &foo; &"}]}}"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst_owned().unwrap(), ["it's \"ok\" & <b>", "AT&T 中 &foo; &"]);
+
+    let error = r#"{"error":{"code":403,"message":"User Rate Limit Exceeded","errors":[{"message":"User Rate Limit Exceeded","domain":"usageLimits","reason":"userRateLimitExceeded"}]}}"#;
+    let error = serde_json::from_str::<Response>(error).unwrap().dst_owned().unwrap_err();
+    assert_eq!(error.reason(), "userRateLimitExceeded");
+    assert!(error.is_retriable());
+
+    let error = r#"{"error":{"code":400,"message":"API key not valid. Please pass a valid API key.","errors":[{"message":"API key not valid. Please pass a valid API key.","domain":"global","reason":"badRequest"}],"status":"INVALID_ARGUMENT"}}"#;
+    assert!(!serde_json::from_str::<Response>(error).unwrap()
+                                                    .dst_owned()
+                                                    .unwrap_err()
+                                                    .is_retriable());
+}
@@ -0,0 +1,582 @@
+//! 语言及其在各翻译 API 中的代码。
+//!
+//! 同一种语言在各 API 中的代码不尽相同：比如日语，百度翻译为 `jp`，腾讯云为 `ja`；
+//! 繁体中文，百度翻译和小牛翻译为 `cht`，腾讯云为 `zh-TW`，有道智云为 `zh-CHT`，
+//! Azure 为 `zh-Hant`。而且并非任意两种语言之间都可以互译，比如腾讯云的阿拉伯语只能与
+//! 英语互译，彩云小译只支持中文与其他语言互译。
+//!
+//! [`Provider::pair`] 把 `-f/-t` 的语言转换成对应 API 的代码，并在请求之前检查语言对。
+use std::str::FromStr;
+
+/// 语言。[`FromStr`] 接受各 API 的代码，不区分大小写：`ja`、`jp` 都解析为 [`Language::Ja`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// 自动识别，只能作为源语言
+    Auto,
+    /// 简体中文
+    Zh,
+    /// 繁体中文
+    ZhTw,
+    /// 英语
+    En,
+    /// 日语
+    Ja,
+    /// 韩语
+    Ko,
+    /// 法语
+    Fr,
+    /// 西班牙语
+    Es,
+    /// 意大利语
+    It,
+    /// 德语
+    De,
+    /// 土耳其语
+    Tr,
+    /// 俄语
+    Ru,
+    /// 葡萄牙语
+    Pt,
+    /// 越南语
+    Vi,
+    /// 印尼语
+    Id,
+    /// 泰语
+    Th,
+    /// 马来语
+    Ms,
+    /// 阿拉伯语
+    Ar,
+    /// 印地语
+    Hi,
+    /// 粤语
+    Yue,
+    /// 文言文
+    Wyw,
+    /// 希腊语
+    El,
+    /// 荷兰语
+    Nl,
+    /// 波兰语
+    Pl,
+    /// 保加利亚语
+    Bg,
+    /// 爱沙尼亚语
+    Et,
+    /// 丹麦语
+    Da,
+    /// 芬兰语
+    Fi,
+    /// 捷克语
+    Cs,
+    /// 罗马尼亚语
+    Ro,
+    /// 斯洛文尼亚语
+    Sl,
+    /// 瑞典语
+    Sv,
+    /// 匈牙利语
+    Hu,
+}
+
+use Language::*;
+
+impl Language {
+    /// 所有语言（不含 [`Language::Auto`]）
+    #[rustfmt::skip]
+    pub const ALL: &'static [Language] = &[
+        Zh, ZhTw, En, Ja, Ko, Fr, Es, It, De, Tr, Ru, Pt, Vi, Id, Th, Ms, Ar, Hi,
+        Yue, Wyw, El, Nl, Pl, Bg, Et, Da, Fi, Cs, Ro, Sl, Sv, Hu,
+    ];
+
+    /// 通用的代码（ISO 639-1，繁体中文为 `zh-TW`）
+    #[rustfmt::skip]
+    pub fn code(self) -> &'static str {
+        match self {
+            Auto => "auto", Zh => "zh", ZhTw => "zh-TW", En => "en", Ja => "ja", Ko => "ko",
+            Fr => "fr", Es => "es", It => "it", De => "de", Tr => "tr", Ru => "ru", Pt => "pt",
+            Vi => "vi", Id => "id", Th => "th", Ms => "ms", Ar => "ar", Hi => "hi",
+            Yue => "yue", Wyw => "wyw", El => "el", Nl => "nl", Pl => "pl", Bg => "bg",
+            Et => "et", Da => "da", Fi => "fi", Cs => "cs", Ro => "ro", Sl => "sl", Sv => "sv",
+            Hu => "hu",
+        }
+    }
+
+    /// 百度翻译的代码
+    ///
+    /// 参考：https://fanyi-api.baidu.com/doc/21
+    #[rustfmt::skip]
+    pub fn baidu(self) -> Option<&'static str> {
+        Some(match self {
+            ZhTw => "cht", Ja => "jp", Ko => "kor", Fr => "fra", Es => "spa", Vi => "vie",
+            Ar => "ara", Bg => "bul", Et => "est", Da => "dan", Fi => "fin", Ro => "rom",
+            Sl => "slo", Sv => "swe",
+            Tr | Id | Ms | Hi => return None,
+            _ => self.code(),
+        })
+    }
+
+    /// 腾讯云的代码
+    ///
+    /// 参考：https://cloud.tencent.com/document/product/551/40566
+    pub fn tencent(self) -> Option<&'static str> {
+        match self {
+            Yue | Wyw | El | Nl | Pl | Bg | Et | Da | Fi | Cs | Ro | Sl | Sv | Hu => None,
+            _ => Some(self.code()),
+        }
+    }
+
+    /// 小牛翻译的代码：不支持自动识别。
+    ///
+    /// 参考：https://niutrans.com/documents/contents/trans_text#languageList
+    pub fn niutrans(self) -> Option<&'static str> {
+        match self {
+            Auto | Wyw => None,
+            ZhTw => Some("cht"),
+            _ => Some(self.code()),
+        }
+    }
+
+    /// 有道智云的代码：简体中文为 `zh-CHS`，繁体中文为 `zh-CHT`。
+    ///
+    /// 参考：https://ai.youdao.com/DOCSIRMA/html/trans/api/wbfy/index.html
+    pub fn youdao(self) -> Option<&'static str> {
+        match self {
+            Wyw => None,
+            Zh => Some("zh-CHS"),
+            ZhTw => Some("zh-CHT"),
+            _ => Some(self.code()),
+        }
+    }
+
+    /// 阿里云的代码
+    ///
+    /// 参考：https://help.aliyun.com/document_detail/215387.html
+    pub fn alibaba(self) -> Option<&'static str> {
+        match self {
+            Wyw => None,
+            ZhTw => Some("zh-tw"),
+            _ => Some(self.code()),
+        }
+    }
+
+    /// 火山引擎的代码
+    ///
+    /// 参考：https://www.volcengine.com/docs/4640/35107
+    pub fn volcengine(self) -> Option<&'static str> {
+        match self {
+            Yue | Wyw => None,
+            ZhTw => Some("zh-Hant"),
+            _ => Some(self.code()),
+        }
+    }
+
+    /// DeepL 的代码：繁体中文只能作为目标语言。请求时转换成大写，见 `deepl::Query::new`。
+    ///
+    /// 参考：https://developers.deepl.com/docs/resources/supported-languages
+    pub fn deepl(self) -> Option<&'static str> {
+        match self {
+            Yue | Wyw | Vi | Th | Ms | Hi => None,
+            ZhTw => Some("zh-hant"),
+            _ => Some(self.code()),
+        }
+    }
+
+    /// LibreTranslate 的代码：繁体中文为 `zt`。实际支持的语言取决于实例安装的语言模型。
+    ///
+    /// 参考：https://libretranslate.com/languages
+    pub fn libretranslate(self) -> Option<&'static str> {
+        match self {
+            Yue | Wyw => None,
+            ZhTw => Some("zt"),
+            _ => Some(self.code()),
+        }
+    }
+
+    /// Azure 的代码：简体中文为 `zh-Hans`，繁体中文为 `zh-Hant`，文言文为 `lzh`。
+    ///
+    /// 参考：https://learn.microsoft.com/azure/ai-services/translator/language-support
+    pub fn azure(self) -> Option<&'static str> {
+        Some(match self {
+            Zh => "zh-Hans",
+            ZhTw => "zh-Hant",
+            Wyw => "lzh",
+            _ => self.code(),
+        })
+    }
+
+    /// Google 翻译的代码
+    ///
+    /// 参考：https://cloud.google.com/translate/docs/languages
+    pub fn google(self) -> Option<&'static str> {
+        match self {
+            Wyw => None,
+            Zh => Some("zh-CN"),
+            _ => Some(self.code()),
+        }
+    }
+
+    /// 彩云小译的代码：只支持中文与 en、ja、ko、es、fr、ru、de 互译，以及 auto 到中文。
+    ///
+    /// 参考：https://docs.caiyunapp.com/blog/2018/09/03/lingocloud-api/
+    pub fn caiyun(self) -> Option<&'static str> {
+        match self {
+            Auto | Zh | En | Ja | Ko | Es | Fr | Ru | De => Some(self.code()),
+            _ => None,
+        }
+    }
+
+    /// 腾讯云中，以 `self` 为源语言时可以翻译成的目标语言
+    #[rustfmt::skip]
+    fn tencent_targets(self) -> &'static [Language] {
+        match self {
+            Zh => &[ZhTw, En, Ja, Ko, Fr, Es, It, De, Tr, Ru, Pt, Vi, Id, Th, Ms, Ar, Hi],
+            ZhTw => &[Zh, En, Ja, Ko, Fr, Es, It, De, Tr, Ru, Pt, Vi, Id, Th, Ms, Ar, Hi],
+            En => &[Zh, ZhTw, Ja, Ko, Fr, Es, It, De, Tr, Ru, Pt, Vi, Id, Th, Ms, Ar, Hi],
+            Ja => &[Zh, ZhTw, En, Ko],
+            Ko => &[Zh, ZhTw, En, Ja],
+            Fr => &[Zh, ZhTw, En, Es, It, De, Tr, Ru, Pt],
+            Es => &[Zh, ZhTw, En, Fr, It, De, Tr, Ru, Pt],
+            It => &[Zh, ZhTw, En, Fr, Es, De, Tr, Ru, Pt],
+            De => &[Zh, ZhTw, En, Fr, Es, It, Tr, Ru, Pt],
+            Tr => &[Zh, ZhTw, En, Fr, Es, It, De, Ru, Pt],
+            Ru => &[Zh, ZhTw, En, Fr, Es, It, De, Tr, Pt],
+            Pt => &[Zh, ZhTw, En, Fr, Es, It, De, Tr, Ru],
+            Vi | Id | Th | Ms => &[Zh, ZhTw, En],
+            Ar | Hi => &[En],
+            Auto => Language::ALL,
+            _ => &[],
+        }
+    }
+}
+
+/// 拉丁字母语言的常用词，用于 [`Language::detect`]
+#[rustfmt::skip]
+const STOPWORDS: &[(Language, &[&str])] = &[
+    (En, &["the", "and", "of", "to", "is", "in", "that", "it", "for", "with", "you", "this",
+           "are", "be", "on", "not", "can", "we"]),
+    (Fr, &["le", "la", "les", "et", "des", "est", "une", "un", "du", "que", "pour", "dans",
+           "pas", "vous", "sur", "avec", "ce", "nous"]),
+    (Es, &["el", "la", "los", "las", "y", "que", "es", "en", "un", "una", "por", "para",
+           "con", "no", "del", "se", "lo", "como"]),
+    (De, &["der", "die", "das", "und", "ist", "nicht", "mit", "ein", "eine", "zu", "den",
+           "sie", "auf", "ich", "für", "auch", "es", "wir"]),
+    (It, &["il", "di", "che", "è", "per", "non", "con", "sono", "della", "gli", "una", "le",
+           "questo", "come", "nel", "anche"]),
+    (Pt, &["o", "os", "as", "que", "não", "um", "uma", "para", "com", "do", "da", "em", "é",
+           "são", "no", "na"]),
+    (Nl, &["het", "een", "en", "van", "is", "niet", "dat", "op", "te", "met", "voor", "zijn",
+           "ook", "wij"]),
+];
+
+impl Language {
+    /// 在本地识别文本的语言：先按文字（汉字、假名、谚文、西里尔字母等）判断，
+    /// 拉丁字母的文本再按特有字母和常用词判断。无法识别时返回 None。
+    ///
+    /// 用于不支持 auto 的 API（比如小牛翻译），结果只是估计。
+    pub fn detect(text: &str) -> Option<Language> {
+        let (mut han, mut kana, mut hangul, mut cyrillic, mut arabic) = (0, 0, 0, 0, 0);
+        let (mut thai, mut devanagari, mut greek, mut latin) = (0, 0, 0, 0);
+        let (mut vietnamese, mut turkish) = (0, 0);
+        for c in text.chars() {
+            match c {
+                '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => han += 1,
+                '\u{3040}'..='\u{30FF}' => kana += 1,
+                '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => {
+                    hangul += 1
+                }
+                '\u{0400}'..='\u{04FF}' => cyrillic += 1,
+                '\u{0600}'..='\u{06FF}' => arabic += 1,
+                '\u{0E00}'..='\u{0E7F}' => thai += 1,
+                '\u{0900}'..='\u{097F}' => devanagari += 1,
+                '\u{0370}'..='\u{03FF}' => greek += 1,
+                _ if c.is_alphabetic() && c.is_ascii() => latin += 1,
+                'ơ' | 'ư' | 'ă' | 'đ' | 'Ơ' | 'Ư' | 'Ă' | 'Đ' | '\u{1EA0}'..='\u{1EF9}' => {
+                    latin += 1;
+                    vietnamese += 1;
+                }
+                'ı' | 'ğ' | 'ş' | 'İ' | 'Ğ' | 'Ş' => {
+                    latin += 1;
+                    turkish += 1;
+                }
+                _ if c.is_alphabetic() && c < '\u{0250}' => latin += 1,
+                _ => (),
+            }
+        }
+        // 日文中混有大量汉字，假名占比达到一成即视为日文
+        if kana > 0 && kana * 10 >= han + kana {
+            return Some(Ja);
+        }
+        // 一个汉字或谚文音节大致相当于一个拉丁字母单词，按 3 倍计数
+        let scripts = [(Zh, (han + kana) * 3),
+                       (Ko, hangul * 3),
+                       (Ru, cyrillic),
+                       (Ar, arabic),
+                       (Th, thai),
+                       (Hi, devanagari),
+                       (El, greek),
+                       (En, latin)];
+        let (lang, count) = scripts.into_iter().max_by_key(|&(_, n)| n)?;
+        if count == 0 {
+            return None;
+        }
+        if lang != En {
+            return Some(lang);
+        }
+        if vietnamese * 20 >= latin {
+            return Some(Vi);
+        }
+        if turkish * 50 >= latin {
+            return Some(Tr);
+        }
+        let words: Vec<String> = text.split(|c: char| !c.is_alphabetic())
+                                     .filter(|w| !w.is_empty())
+                                     .map(str::to_lowercase)
+                                     .collect();
+        STOPWORDS.iter()
+                 .map(|(lang, stop)| {
+                     (*lang, words.iter().filter(|w| stop.contains(&w.as_str())).count())
+                 })
+                 .filter(|&(_, n)| n > 0)
+                 .max_by_key(|&(_, n)| n)
+                 .map_or(Some(En), |(lang, _)| Some(lang))
+    }
+}
+
+impl FromStr for Language {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        match lower.as_str() {
+            "auto" => Ok(Auto),
+            "zh" | "zh-cn" | "zh-hans" | "zh-chs" => Ok(Zh),
+            "zh-tw" | "zh-hant" | "zh-cht" | "cht" | "zt" => Ok(ZhTw),
+            "lzh" => Ok(Wyw),
+            "jp" => Ok(Ja),
+            "kor" => Ok(Ko),
+            "fra" => Ok(Fr),
+            "spa" => Ok(Es),
+            "vie" => Ok(Vi),
+            "ara" => Ok(Ar),
+            "bul" => Ok(Bg),
+            "est" => Ok(Et),
+            "dan" => Ok(Da),
+            "fin" => Ok(Fi),
+            "rom" => Ok(Ro),
+            "slo" => Ok(Sl),
+            "swe" => Ok(Sv),
+            code => Language::ALL.iter()
+                                 .find(|l| l.code() == code)
+                                 .copied()
+                                 .ok_or_else(|| Error::Unknown(s.into())),
+        }
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(self.code()) }
+}
+
+/// 有语言代码差异或者语言对限制的翻译 API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Baidu,
+    Tencent,
+    Niutrans,
+    Youdao,
+    Alibaba,
+    Volcengine,
+    DeepL,
+    LibreTranslate,
+    Azure,
+    Google,
+    Caiyun,
+}
+
+impl Provider {
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::Baidu => "百度翻译",
+            Provider::Tencent => "腾讯云",
+            Provider::Niutrans => "小牛翻译",
+            Provider::Youdao => "有道智云",
+            Provider::Alibaba => "阿里云",
+            Provider::Volcengine => "火山引擎",
+            Provider::DeepL => "DeepL",
+            Provider::LibreTranslate => "LibreTranslate",
+            Provider::Azure => "Azure 翻译",
+            Provider::Google => "Google 翻译",
+            Provider::Caiyun => "彩云小译",
+        }
+    }
+
+    /// 语言在该 API 中的代码；不支持时为 None。
+    pub fn code(self, lang: Language) -> Option<&'static str> {
+        match self {
+            Provider::Baidu => lang.baidu(),
+            Provider::Tencent => lang.tencent(),
+            Provider::Niutrans => lang.niutrans(),
+            Provider::Youdao => lang.youdao(),
+            Provider::Alibaba => lang.alibaba(),
+            Provider::Volcengine => lang.volcengine(),
+            Provider::DeepL => lang.deepl(),
+            Provider::LibreTranslate => lang.libretranslate(),
+            Provider::Azure => lang.azure(),
+            Provider::Google => lang.google(),
+            Provider::Caiyun => lang.caiyun(),
+        }
+    }
+
+    /// 两种语言（都已支持）之间能否互译
+    fn supports(self, from: Language, to: Language) -> bool {
+        match self {
+            Provider::Tencent => from.tencent_targets().contains(&to),
+            Provider::Caiyun => from == Zh || to == Zh,
+            // DeepL 的繁体中文只能作为目标语言
+            Provider::DeepL => from != ZhTw,
+            _ => true,
+        }
+    }
+
+    /// 以 `from` 为源语言时可以翻译成的目标语言
+    pub fn targets(self, from: Language) -> Vec<Language> {
+        Language::ALL.iter()
+                     .copied()
+                     .filter(|&to| to != from && self.code(to).is_some() && self.supports(from, to))
+                     .collect()
+    }
+
+    /// 解析并检查目标语言，返回该 API 的代码。用于原语言为 auto、需要在翻译前识别的情况。
+    pub fn target(self, to: &str) -> Result<&'static str, Error> {
+        let to: Language = to.parse()?;
+        if to == Auto {
+            return Err(Error::AutoTarget);
+        }
+        self.code(to).ok_or(Error::Unsupported { provider: self, lang: to })
+    }
+
+    /// 解析 `-f/-t` 的语言并检查语言对，返回该 API 的原语言代码和目标语言代码。
+    pub fn pair(self, from: &str, to: &str) -> Result<(&'static str, &'static str), Error> {
+        let (from, to): (Language, Language) = (from.parse()?, to.parse()?);
+        if to == Auto {
+            return Err(Error::AutoTarget);
+        }
+        if from == to {
+            return Err(Error::Same(from));
+        }
+        let code = |lang| self.code(lang).ok_or(Error::Unsupported { provider: self, lang });
+        let pair = (code(from)?, code(to)?);
+        if !self.supports(from, to) {
+            return Err(Error::Pair { provider: self, from, to });
+        }
+        Ok(pair)
+    }
+}
+
+/// 语言错误：在发送请求之前返回。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// 无法识别的语言代码
+    Unknown(String),
+    /// 目标语言不可为 auto
+    AutoTarget,
+    /// 原语言与目标语言相同
+    Same(Language),
+    /// 该 API 不支持这种语言
+    Unsupported { provider: Provider, lang: Language },
+    /// 该 API 不支持这两种语言之间的翻译
+    Pair {
+        provider: Provider,
+        from:     Language,
+        to:       Language,
+    },
+}
+
+impl std::error::Error for Error {}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Unknown(s) => write!(f, "无法识别语言代码 `{s}`"),
+            Error::AutoTarget => write!(f, "目标语言不可设置为 auto"),
+            Error::Same(lang) => write!(f, "原语言与目标语言相同：`{lang}`"),
+            Error::Unsupported { provider, lang } => {
+                write!(f, "{}不支持语言 `{lang}`", provider.name())
+            }
+            Error::Pair { provider, from, to } => {
+                let targets = provider.targets(*from).iter().map(|l| l.code()).collect::<Vec<_>>();
+                write!(f,
+                       "{}不支持从 `{from}` 翻译到 `{to}`：`{from}` 只能翻译成 {}",
+                       provider.name(),
+                       targets.join("、"))
+            }
+        }
+    }
+}
+
+#[test]
+fn language_test() {
+    assert_eq!("jp".parse(), Ok(Ja));
+    assert_eq!("JA".parse(), Ok(Ja));
+    assert_eq!("zh-CHS".parse(), Ok(Zh));
+    assert_eq!("cht".parse(), Ok(ZhTw));
+    assert_eq!("zn".parse::<Language>(), Err(Error::Unknown("zn".into())));
+
+    assert_eq!(Provider::Baidu.pair("ja", "zh-TW"), Ok(("jp", "cht")));
+    assert_eq!(Provider::Tencent.pair("jp", "cht"), Ok(("ja", "zh-TW")));
+    assert_eq!(Provider::Niutrans.pair("kor", "zh-TW"), Ok(("ko", "cht")));
+    assert_eq!(Provider::Baidu.pair("auto", "kor"), Ok(("auto", "kor")));
+
+    assert_eq!(Provider::Tencent.pair("en", "auto"), Err(Error::AutoTarget));
+    assert_eq!(Provider::Tencent.pair("en", "en"), Err(Error::Same(En)));
+    assert_eq!(Provider::Tencent.pair("en", "ar"), Ok(("en", "ar")));
+    let err = Provider::Tencent.pair("ar", "zh").unwrap_err();
+    assert_eq!(err, Error::Pair { provider: Provider::Tencent,
+                                  from:     Ar,
+                                  to:       Zh, });
+    assert_eq!(err.to_string(), "腾讯云不支持从 `ar` 翻译到 `zh`：`ar` 只能翻译成 en");
+    assert_eq!(Provider::Tencent.pair("en", "nl"),
+               Err(Error::Unsupported { provider: Provider::Tencent, lang: Nl }));
+    assert_eq!(Provider::Niutrans.pair("auto", "zh"),
+               Err(Error::Unsupported { provider: Provider::Niutrans, lang: Auto }));
+    assert!(Provider::Baidu.pair("en", "tr").is_err());
+    assert_eq!(Provider::Niutrans.target("cht"), Ok("cht"));
+    assert_eq!(Provider::Niutrans.target("auto"), Err(Error::AutoTarget));
+
+    assert_eq!(Provider::Youdao.pair("en", "zh"), Ok(("en", "zh-CHS")));
+    assert_eq!(Provider::Youdao.pair("jp", "cht"), Ok(("ja", "zh-CHT")));
+    assert_eq!(Provider::Azure.pair("auto", "zh"), Ok(("auto", "zh-Hans")));
+    assert_eq!(Provider::Azure.pair("kor", "zh-TW"), Ok(("ko", "zh-Hant")));
+    assert_eq!(Provider::Google.pair("en", "zh"), Ok(("en", "zh-CN")));
+    assert_eq!(Provider::LibreTranslate.pair("en", "cht"), Ok(("en", "zt")));
+    assert_eq!(Provider::DeepL.pair("en", "zh-TW"), Ok(("en", "zh-hant")));
+    assert!(Provider::DeepL.pair("cht", "en").is_err());
+    assert_eq!(Provider::DeepL.pair("en", "vi"),
+               Err(Error::Unsupported { provider: Provider::DeepL, lang: Vi }));
+
+    // 彩云小译：中文与其他语言互译，或者 auto 到中文
+    assert_eq!(Provider::Caiyun.pair("zh", "jp"), Ok(("zh", "ja")));
+    assert_eq!(Provider::Caiyun.pair("kor", "zh-CHS"), Ok(("ko", "zh")));
+    assert_eq!(Provider::Caiyun.pair("auto", "zh"), Ok(("auto", "zh")));
+    let err = Provider::Caiyun.pair("en", "ja").unwrap_err();
+    assert_eq!(err.to_string(), "彩云小译不支持从 `en` 翻译到 `ja`：`en` 只能翻译成 zh");
+    assert!(Provider::Caiyun.pair("auto", "en").is_err());
+    assert!(Provider::Caiyun.pair("cht", "zh").is_err());
+}
+
+#[test]
+fn detect_test() {
+    assert_eq!(Language::detect("We add the callback to the collection of callbacks to run."),
+               Some(En));
+    assert_eq!(Language::detect("これは日本語の文章です。漢字も含まれています。"), Some(Ja));
+    assert_eq!(Language::detect("这是一段中文，其中夹杂着 English words。"), Some(Zh));
+    assert_eq!(Language::detect("이것은 한국어 문장입니다."), Some(Ko));
+    assert_eq!(Language::detect("Это русский текст."), Some(Ru));
+    assert_eq!(Language::detect("Le chat est sur la table et nous sommes dans la maison."),
+               Some(Fr));
+    assert_eq!(Language::detect("Der Hund ist nicht mit der Katze auf dem Tisch."), Some(De));
+    assert_eq!(Language::detect("El perro y el gato están en la casa, como siempre."), Some(Es));
+    assert_eq!(Language::detect("Tôi không biết tiếng Việt nhiều lắm."), Some(Vi));
+    assert_eq!(Language::detect("`code` 123 !?"), Some(En));
+    assert_eq!(Language::detect("123 !?"), None);
+}
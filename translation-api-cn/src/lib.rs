@@ -7,10 +7,42 @@ pub mod tencent;
 #[cfg(feature = "niutrans")]
 pub mod niutrans;
 
+#[cfg(feature = "youdao")]
+pub mod youdao;
+
+#[cfg(feature = "alibaba")]
+pub mod alibaba;
+
+#[cfg(feature = "volcengine")]
+pub mod volcengine;
+
+#[cfg(feature = "deepl")]
+pub mod deepl;
+
+#[cfg(feature = "llm")]
+pub mod llm;
+
+#[cfg(feature = "libretranslate")]
+pub mod libretranslate;
+
+#[cfg(feature = "azure")]
+pub mod azure;
+
+#[cfg(feature = "google")]
+pub mod google;
+
+#[cfg(feature = "caiyun")]
+pub mod caiyun;
+
+pub mod language;
+pub use language::Language;
+
 /// 单次调用各 API 时，被限制的“字符”单位
 ///
-/// 对于百度翻译，为 Byte；对于腾讯云和小牛翻译，为 Char。
-#[derive(Debug, serde::Deserialize)]
+/// 对于百度翻译和 DeepL，为
+/// Byte；对于腾讯云、小牛翻译、有道智云、阿里云、火山引擎、Azure、Google 和
+/// 彩云小译，为 Char。
+#[derive(Debug, Clone, serde::Deserialize)]
 pub enum Limit {
     #[serde(rename = "bytes")]
     Byte(usize),
@@ -24,3 +56,60 @@ impl Limit {
         l
     }
 }
+
+/// 请求失败时的重试策略：只有暂时性的错误（比如超出 QPS 限制、请求超时）才会重试。
+///
+/// 第 n 次重试前等待 `backoff * 2^(n-1)` 毫秒，但不超过 `max_backoff` 毫秒。
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct Retry {
+    /// 最多请求的次数（包括第一次请求），默认为 3。为 1 时不重试。
+    pub attempts:    u8,
+    /// 第一次重试前等待的毫秒数，默认为 1000。
+    pub backoff:     u64,
+    /// 每次重试前等待的最大毫秒数，默认为 10000。
+    pub max_backoff: u64,
+    /// 是否在等待时间上增加随机抖动，默认为 true。
+    pub jitter:      bool,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self { attempts:    3,
+               backoff:     1000,
+               max_backoff: 10000,
+               jitter:      true, }
+    }
+}
+
+impl Retry {
+    /// 第 `n` 次重试（从 1 开始）前等待的时间（不含随机抖动）。
+    pub fn backoff(&self, n: u8) -> std::time::Duration {
+        let exp = u32::from(n.saturating_sub(1)).min(63);
+        std::time::Duration::from_millis(self.backoff
+                                             .saturating_mul(1 << exp)
+                                             .min(self.max_backoff))
+    }
+}
+
+/// 术语：对应各 API 原生的术语功能，在配置文件中写作 `glossary = { ... }`。
+///
+/// 各 API 只使用自己支持的字段：
+/// - 百度翻译：[`custom`][`Glossary::custom`]
+/// - 小牛翻译：[`dict`][`Glossary::dict`] 和 [`memory`][`Glossary::memory`]
+/// - 腾讯云：[`untranslated`][`Glossary::untranslated`]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Glossary {
+    /// 百度翻译：使用在控制台中维护的自定义术语库（`action=1`）。
+    pub custom:       bool,
+    /// 小牛翻译：术语词典子库 ID（`dictNo`）。
+    pub dict:         String,
+    /// 小牛翻译：翻译记忆子库 ID（`memoryNo`）。
+    pub memory:       String,
+    /// 腾讯云：不翻译的文本，比如产品名、人名（`UntranslatedText`）。
+    ///
+    /// 腾讯云每次请求只能指定一个不翻译的文本，而且只有逐段翻译的接口支持：
+    /// 含有这些文本的段落会单独请求，每段只保护其中最先列出的那一个。
+    pub untranslated: Vec<String>,
+}
@@ -0,0 +1,157 @@
+use crate::{Limit, Retry};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+pub const URL: &str = "http://127.0.0.1:5000";
+
+/// 翻译前的必要信息：以 JSON 格式提交。
+///
+/// 参考：https://libretranslate.com/docs/#/translate/post_translate
+#[derive(Debug, Serialize)]
+pub struct Query<'q> {
+    /// 请求翻译 query：以数组的形式批量翻译，译文数组与之一一对应。
+    pub q:       &'q [&'q str],
+    /// 翻译源语言，可设置为 auto
+    #[serde(rename = "source")]
+    pub from:    &'q str,
+    /// 翻译目标语言
+    #[serde(rename = "target")]
+    pub to:      &'q str,
+    /// text 或者 html
+    pub format:  &'q str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<&'q str>,
+}
+
+impl<'q> Query<'q> {
+    pub fn new(q: &'q [&'q str], from: &'q str, to: &'q str, user: &'q User) -> Self {
+        Self { q,
+               from,
+               to,
+               format: &user.format,
+               api_key: user.api_key.as_deref() }
+    }
+}
+
+/// 自部署的 LibreTranslate 实例的信息
+#[derive(Debug, Deserialize)]
+#[serde(rename = "libretranslate")] // for config or cmd
+pub struct User {
+    /// 实例地址，请求发往 `{url}/translate`，默认为 [`URL`]。
+    #[serde(default = "default_url")]
+    pub url:     String,
+    /// 实例开启了 API key 认证时需要。
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// 原文格式：text | html，默认为 text。
+    #[serde(default = "default_format")]
+    pub format:  String,
+    /// 每秒请求数（QPS），默认为 0，即不限流：实例由自己部署。
+    #[serde(default)]
+    pub qps:     u8,
+    /// 每次请求的字符上限，默认为 Char(5000)。
+    #[serde(default = "default_limit")]
+    pub limit:   Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:   Retry,
+}
+
+fn default_url() -> String { String::from(URL) }
+fn default_format() -> String { String::from("text") }
+fn default_limit() -> Limit { Limit::Char(5000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { url:     default_url(),
+               api_key: None,
+               format:  default_format(),
+               qps:     0,
+               limit:   default_limit(),
+               retry:   Retry::default(), }
+    }
+}
+
+impl User {
+    /// 请求地址：`{url}/translate`
+    pub fn url(&self) -> String { format!("{}/translate", self.url.trim_end_matches('/')) }
+}
+
+/// 成功时的响应信息。`q` 为数组时，译文也是数组。
+#[derive(Debug, Deserialize)]
+pub struct Response<'r> {
+    #[serde(rename = "translatedText", borrow)]
+    pub dst: Vec<Cow<'r, str>>,
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容。
+    pub fn dst(&self) -> impl Iterator<Item = &str> { self.dst.iter().map(|s| s.as_ref()) }
+
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> Vec<String> { self.dst.into_iter().map(|s| s.into()).collect() }
+}
+
+/// 错误处理 / HTTP 状态码
+#[derive(Debug, Clone)]
+pub struct Error {
+    pub status: u16,
+    /// 响应体中的 `error` 字段，可能为空
+    pub msg:    String,
+}
+
+impl Error {
+    /// 根据非 2xx 的 HTTP 状态码和响应体构造错误。
+    pub fn new(status: u16, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            #[serde(default)]
+            error: String,
+        }
+        let msg = serde_json::from_slice::<Body>(body).map(|b| b.error).unwrap_or_default();
+        Self { status, msg }
+    }
+
+    pub fn solution(&self) -> &str {
+        match self.status {
+            400 => "请求参数错误。\n解决方法：请检查语言代码是否被实例所支持。",
+            403 => "被禁止访问。\n解决方法：请检查 api_key。",
+            404 => "接口不存在。\n解决方法：请检查 url。",
+            429 => "请求过于频繁。\n解决方法：请降低 qps，或者调整实例的 --req-limit。",
+            500..=599 => "实例内部错误。\n解决方法：请重试。",
+            _ => "未知错误。",
+        }
+    }
+
+    /// 是否为暂时性的错误：请求过于频繁或者实例内部错误。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool { matches!(self.status, 429 | 500..=599) }
+}
+
+impl std::error::Error for Error {}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,
+               "HTTP 状态码：`{}`\n错误信息：`{}`\n错误含义：{}\n以上内容由 LibreTranslate 返回",
+               self.status,
+               self.msg,
+               self.solution())
+    }
+}
+
+#[test]
+fn response_test() {
+    let user = User { api_key: Some("xx".into()), ..User::default() };
+    assert_eq!(user.url(), "http://127.0.0.1:5000/translate");
+    let query = Query::new(&["hi", "there"], "en", "zh", &user);
+    assert_eq!(serde_json::to_string(&query).unwrap(),
+               r#"{"q":["hi","there"],"source":"en","target":"zh","format":"text","api_key":"xx"}"#);
+
+    let success = r#"{"translatedText":["嗨","那里"]}"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst().collect::<Vec<_>>(), ["嗨", "那里"]);
+
+    let error = Error::new(400, br#"{"error":"zz is not supported"}"#);
+    assert_eq!(error.msg, "zz is not supported");
+    assert!(!error.is_retriable());
+    assert!(Error::new(429, b"").is_retriable());
+}
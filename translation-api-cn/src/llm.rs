@@ -0,0 +1,226 @@
+use crate::{Limit, Retry};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+pub const BASE_URL: &str = "https://api.openai.com/v1";
+
+/// 默认的系统提示词模板：`{from}` 和 `{to}` 会被替换成原语言和目标语言。
+pub const PROMPT: &str = "You are a professional translator. The user sends a JSON array of \
+                          objects like {\"id\": 1, \"text\": \"...\"}. Translate every `text` \
+                          from {from} to {to}. Keep markdown syntax, inline code, links and \
+                          placeholders unchanged. Reply with only a JSON array of the same \
+                          length, where each item is {\"id\": <the same id>, \"text\": \
+                          <translation>}, without any explanation or code fence.";
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Error {
+    #[error("HTTP 状态码：`{status}`\n错误信息：`{msg}`\n错误含义：{}\n以上内容由 LLM API 返回",
+            solution(.status))]
+    Api { status: u16, msg: String },
+    #[error("模型回复的内容不是预期的 JSON 数组：{0}")]
+    Format(String),
+}
+
+fn solution(status: &u16) -> &'static str {
+    match status {
+        400 => "请求参数错误。\n解决方法：请检查 model 和 temperature。",
+        401 | 403 => "认证失败。\n解决方法：请检查 api_key。",
+        404 => "模型或者接口不存在。\n解决方法：请检查 base_url 和 model。",
+        429 => "请求过于频繁或者额度不足。\n解决方法：请降低您的调用频率。",
+        500..=599 => "服务端内部错误。\n解决方法：请重试。",
+        _ => "未知错误。",
+    }
+}
+
+impl Error {
+    /// 根据非 2xx 的 HTTP 状态码和响应体构造错误。
+    pub fn new(status: u16, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            error: Inner,
+        }
+        #[derive(Deserialize)]
+        struct Inner {
+            #[serde(default)]
+            message: String,
+        }
+        let msg = serde_json::from_slice::<Body>(body).map(|b| b.error.message)
+                                                      .unwrap_or_else(|_| {
+                                                          String::from_utf8_lossy(body).into()
+                                                      });
+        Error::Api { status, msg }
+    }
+
+    /// 是否为暂时性的错误：请求过于频繁、服务端异常，或者模型的回复不合格式。
+    /// 模型的回复具有随机性，重新请求可能得到合格的回复。
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Error::Api { status, .. } => matches!(status, 429 | 500..=599),
+            Error::Format(_) => true,
+        }
+    }
+}
+
+/// 账户信息以及模型的参数
+#[derive(Debug, Deserialize)]
+#[serde(rename = "llm")] // for config or cmd
+pub struct User {
+    /// 兼容 OpenAI 的接口地址，请求发往 `{base_url}/chat/completions`。
+    /// 比如本地的 llama.cpp 或者 vLLM 服务：`http://127.0.0.1:8080/v1`。
+    #[serde(default = "default_base_url")]
+    pub base_url:    String,
+    /// 以 `Authorization: Bearer` 请求头提交；本地服务可以为空。
+    #[serde(default)]
+    pub api_key:     String,
+    /// 模型名称
+    pub model:       String,
+    /// 系统提示词模板，默认为 [`PROMPT`]。
+    #[serde(default = "default_prompt")]
+    pub prompt:      String,
+    /// 采样温度，默认为 0。
+    #[serde(default)]
+    pub temperature: f32,
+    /// 每秒请求数（QPS），默认为 1。
+    #[serde(default = "default_qps")]
+    pub qps:         u8,
+    /// 每次请求的字符上限，默认为 Char(2000)。
+    #[serde(default = "default_limit")]
+    pub limit:       Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:       Retry,
+}
+
+fn default_base_url() -> String { String::from(BASE_URL) }
+fn default_prompt() -> String { String::from(PROMPT) }
+fn default_qps() -> u8 { 1 }
+fn default_limit() -> Limit { Limit::Char(2000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { base_url:    default_base_url(),
+               api_key:     String::new(),
+               model:       String::new(),
+               prompt:      default_prompt(),
+               temperature: 0.,
+               qps:         default_qps(),
+               limit:       default_limit(),
+               retry:       Retry::default(), }
+    }
+}
+
+impl User {
+    /// 请求地址：`{base_url}/chat/completions`
+    pub fn url(&self) -> String {
+        format!("{}/chat/completions", self.base_url.trim_end_matches('/'))
+    }
+
+    /// 填充了语言的系统提示词
+    pub fn system_prompt(&self, from: &str, to: &str) -> String {
+        self.prompt.replace("{from}", from).replace("{to}", to)
+    }
+}
+
+/// 带编号的段落：发送和回复都使用这个格式。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Segment<'s> {
+    pub id:   usize,
+    #[serde(borrow)]
+    pub text: Cow<'s, str>,
+}
+
+/// 把段落编号（从 1 开始），序列化成 JSON 数组。
+pub fn numbered(q: &[&str]) -> String {
+    let segments: Vec<_> = q.iter()
+                            .enumerate()
+                            .map(|(i, s)| Segment { id: i + 1, text: (*s).into() })
+                            .collect();
+    serde_json::to_string(&segments).unwrap_or_default()
+}
+
+/// 请求体
+#[derive(Debug, Serialize)]
+pub struct Request<'r> {
+    pub model:       &'r str,
+    pub temperature: f32,
+    pub messages:    [Message<'r>; 2],
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Message<'m> {
+    pub role:    Cow<'m, str>,
+    #[serde(borrow)]
+    pub content: Cow<'m, str>,
+}
+
+impl<'r> Request<'r> {
+    pub fn new(user: &'r User, q: &[&str], from: &str, to: &str) -> Self {
+        let system = Message { role:    "system".into(),
+                               content: user.system_prompt(from, to).into(), };
+        let user_msg = Message { role:    "user".into(),
+                                 content: numbered(q).into(), };
+        Self { model:       &user.model,
+               temperature: user.temperature,
+               messages:    [system, user_msg], }
+    }
+}
+
+/// 成功时的响应信息
+#[derive(Debug, Deserialize)]
+pub struct Response<'r> {
+    #[serde(borrow)]
+    pub choices: Vec<Choice<'r>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Choice<'r> {
+    #[serde(borrow)]
+    pub message: Message<'r>,
+}
+
+impl<'r> Response<'r> {
+    /// 第一个回复的内容
+    pub fn content(&self) -> Result<&str, Error> {
+        self.choices
+            .first()
+            .map(|c| c.message.content.as_ref())
+            .ok_or_else(|| Error::Format("choices 为空".into()))
+    }
+
+    /// 提取翻译内容，并校验段落编号。段落数量由调用方校验。
+    pub fn dst_owned(&self) -> Result<Vec<String>, Error> { parse_reply(self.content()?) }
+}
+
+/// 解析模型的回复：允许回复被 ```json 代码块包裹；按编号排序后，
+/// 编号必须为 1..=n，n 为回复的段落数量。
+pub fn parse_reply(reply: &str) -> Result<Vec<String>, Error> {
+    let reply = reply.trim();
+    let reply = reply.strip_prefix("```json")
+                     .or_else(|| reply.strip_prefix("```"))
+                     .and_then(|s| s.strip_suffix("```"))
+                     .unwrap_or(reply);
+    let mut segments: Vec<Segment> =
+        serde_json::from_str(reply).map_err(|e| Error::Format(format!("{e}\n{reply}")))?;
+    segments.sort_by_key(|s| s.id);
+    if segments.iter().enumerate().any(|(i, s)| s.id != i + 1) {
+        return Err(Error::Format(format!("编号不是 1..={}\n{reply}", segments.len())));
+    }
+    Ok(segments.into_iter().map(|s| s.text.into_owned()).collect())
+}
+
+#[test]
+fn reply_test() {
+    assert_eq!(numbered(&["hi", "a \"b\""]), r#"[{"id":1,"text":"hi"},{"id":2,"text":"a \"b\""}]"#);
+
+    let reply = "```json\n[{\"id\":2,\"text\":\"那里\"},{\"id\":1,\"text\":\"嗨\"}]\n```";
+    assert_eq!(parse_reply(reply).unwrap(), ["嗨", "那里"]);
+
+    let err = parse_reply(r#"[{"id":1,"text":"嗨"},{"id":3,"text":"那里"}]"#).unwrap_err();
+    assert!(matches!(err, Error::Format(_)));
+    assert!(err.is_retriable());
+    assert!(matches!(parse_reply("嗨\n那里"), Err(Error::Format(_))));
+
+    let err = Error::new(401, br#"{"error":{"message":"Incorrect API key provided"}}"#);
+    assert!(matches!(&err, Error::Api { status: 401, msg } if msg == "Incorrect API key provided"));
+    assert!(!err.is_retriable());
+}
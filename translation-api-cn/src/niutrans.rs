@@ -1,4 +1,4 @@
-use crate::Limit;
+use crate::{Glossary, Limit, Retry};
 use std::borrow::Cow;
 
 use serde::{Deserialize, Serialize};
@@ -15,13 +15,13 @@ pub struct Query<'q> {
     /// TODO: 在传入之前应该把文字控制在 6000 字节以内（汉字约为 2000 个字符），
     ///       超过 6000 字节要分段请求。
     pub q:    &'q str,
-    /// 翻译源语言，不可设置为 auto
+    /// 翻译源语言，不可设置为 auto。使用小牛翻译的代码，见 [`Language::niutrans`]。
     ///
-    /// TODO：变成 Option + enum 类型，None 表示 auto
+    /// [`Language::niutrans`]: crate::Language::niutrans
     pub from: &'q str,
-    /// 翻译目标语言，不可设置为 auto
+    /// 翻译目标语言，不可设置为 auto。可用 [`Provider::pair`] 检查语言对。
     ///
-    /// TODO：和 `from` 共用 enum 类型，但无需是 Option 类型
+    /// [`Provider::pair`]: crate::language::Provider::pair
     pub to:   &'q str,
 }
 
@@ -38,20 +38,29 @@ impl<'q> Query<'q> {
 #[serde(rename = "niutrans")] // for config or cmd
 pub struct User {
     /// 用户申请得到的密钥
-    pub key:    String,
-    /// 默认为 50
+    pub key:      String,
+    /// 每秒请求数（QPS），默认为 50。
     #[serde(default = "default_qps")]
-    pub qps:    u8,
+    pub qps:      u8,
     /// 每秒并发请求的限制，默认为 Char(5000)。
     #[serde(default = "default_limit")]
     // #[serde(skip_deserializing)]
     pub limit: Limit,
-    /// 术语词典子库 ID
+    /// 术语词典子库 ID。与 `glossary.dict` 相同，同时设置时以 `glossary` 为准。
     #[serde(default)]
-    pub dict:   String,
-    /// 翻译记忆子库 ID
+    pub dict:     String,
+    /// 翻译记忆子库 ID。与 `glossary.memory` 相同，同时设置时以 `glossary` 为准。
+    #[serde(default)]
+    pub memory:   String,
+    /// 术语：只使用 [`Glossary::dict`] 和 [`Glossary::memory`]。
+    #[serde(default)]
+    pub glossary: Glossary,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:    Retry,
+    /// 请求地址，默认为 [`URL`]。
     #[serde(default)]
-    pub memory: String,
+    pub endpoint: Option<String>,
 }
 
 fn default_qps() -> u8 { 50 }
@@ -59,11 +68,33 @@ fn default_limit() -> Limit { Limit::Char(5000) }
 
 impl Default for User {
     fn default() -> Self {
-        Self { key:    String::new(),
-               qps:    default_qps(),
-               limit:  default_limit(),
-               dict:   String::new(),
-               memory: String::new(), }
+        Self { key:      String::new(),
+               qps:      default_qps(),
+               limit:    default_limit(),
+               dict:     String::new(),
+               memory:   String::new(),
+               glossary: Glossary::default(),
+               retry:    Retry::default(),
+               endpoint: None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则为 [`URL`]。
+    pub fn url(&self) -> &str { self.endpoint.as_deref().unwrap_or(URL) }
+
+    /// 术语词典子库 ID
+    pub fn dict(&self) -> &str { or(&self.glossary.dict, &self.dict) }
+
+    /// 翻译记忆子库 ID
+    pub fn memory(&self) -> &str { or(&self.glossary.memory, &self.memory) }
+}
+
+fn or<'s>(a: &'s str, b: &'s str) -> &'s str {
+    if a.is_empty() {
+        b
+    } else {
+        a
     }
 }
 
@@ -86,8 +117,8 @@ impl<'f> Form<'f> {
                from:     query.from,
                to:       query.to,
                apikey:   &user.key,
-               dict:     &user.dict,
-               memory:   &user.dict, }
+               dict:     user.dict(),
+               memory:   user.memory(), }
     }
 }
 
@@ -191,6 +222,9 @@ impl Error {
             _ => "未知错误。",
         }
     }
+
+    /// 是否为暂时性的错误：超出 QPS 限制或者请求处理超时。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool { matches!(self.code.as_bytes(), b"10001" | b"13008") }
 }
 
 #[test]
@@ -209,4 +243,19 @@ fn response_test() {
                    running out\",\"src_text\":\"hi\\nthere\",\"apikey\":\"xx\"}";
     let res: Response = serde_json::from_str(error).unwrap();
     assert!(res.dst().is_err());
+    assert!(!res.dst_owned().unwrap_err().is_retriable());
+}
+
+#[test]
+fn form_test() {
+    let mut user = User { dict: "1".into(),
+                          memory: "2".into(),
+                          ..Default::default() };
+    let query = Query::new("hi", "en", "zh");
+    let form = query.form(&user);
+    assert_eq!((form.dict, form.memory), ("1", "2"));
+
+    user.glossary.memory = "3".into();
+    let form = query.form(&user);
+    assert_eq!((form.dict, form.memory), ("1", "3"));
 }
@@ -1,11 +1,16 @@
-use crate::Limit;
+use crate::{Glossary, Limit, Retry};
 use hmac::{
     digest::{CtOutput as HmacOutput, InvalidLength},
     Hmac, Mac,
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::PathBuf,
+    sync::atomic::{AtomicI64, Ordering},
+};
 use time::OffsetDateTime;
 
 mod region;
@@ -18,10 +23,15 @@ pub mod ser_json;
 mod hash;
 pub use hash::*;
 
+mod signer;
+pub use signer::{http_date, Clock, Signer, SkewedClock, SystemClock};
+
 mod response;
-pub use response::{Response, ResponseError, ResponseInner};
+pub use response::{DetectResponse, Response, ResponseError, ResponseInner, TextResponse};
 
 pub const URL: &str = "https://tmt.tencentcloudapi.com";
+/// [`URL`] 的 host：就近接入
+pub const HOST: &str = "tmt.tencentcloudapi.com";
 
 /// HMAC-SHA256 算法
 pub type HmacSha256 = Hmac<Sha256>;
@@ -43,15 +53,18 @@ pub enum Error {
 /// https://cloud.tencent.com/document/product/551/40566
 #[derive(Debug, Serialize)]
 pub struct Query<'q> {
-    /// 翻译源语言，可设置为 auto
+    /// 翻译源语言，可设置为 auto。使用腾讯云的代码，见 [`Language::tencent`]。
     ///
-    /// TODO：变成 enum 类型
+    /// [`Language::tencent`]: crate::Language::tencent
     #[serde(rename = "Source")]
     pub from:      &'q str,
     /// 翻译目标语言，不可设置为 auto
     ///
-    /// TODO：和 `from` 共用 enum 类型，但是并非任意两个语言之间可以互译。
-    /// 比如 `ar（阿拉伯语）：en（英语）` 表明阿拉伯语只能从英语中翻译过去。
+    /// 并非任意两个语言之间可以互译，比如阿拉伯语只能与英语互译。
+    /// 可用 [`Provider::pair`] 检查语言对。
+    ///
+    /// [`Provider::pair`]: crate::language::Provider::pair
+    ///
     /// 请求翻译 query，必须为 UTF-8 编码。
     ///
     /// TODO: 在传入之前应该把文字控制在 2000 以内,
@@ -91,6 +104,66 @@ impl<'q> Query<'q> {
     }
 }
 
+/// 翻译单个段落（TextTranslate）前的必要信息：与 [`Query`] 不同，可以指定不翻译的文本。
+///
+/// https://cloud.tencent.com/document/product/551/15619
+#[derive(Debug, Serialize)]
+pub struct TextQuery<'q> {
+    #[serde(rename = "SourceText")]
+    pub q:            &'q str,
+    #[serde(rename = "Source")]
+    pub from:         &'q str,
+    #[serde(rename = "Target")]
+    pub to:           &'q str,
+    #[serde(rename = "ProjectId")]
+    pub projectid:    u8,
+    /// 不翻译的文本，每次请求只能指定一个
+    #[serde(rename = "UntranslatedText")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub untranslated: Option<&'q str>,
+}
+
+impl<'q> TextQuery<'q> {
+    #[rustfmt::skip]
+    pub fn new(q: &'q str, from: &'q str, to: &'q str, projectid: u8) -> Self {
+        Self { q, from, to, projectid, untranslated: None }
+    }
+}
+
+/// 识别文本语言（LanguageDetect）前的必要信息
+///
+/// https://cloud.tencent.com/document/product/551/15620
+#[derive(Debug, Serialize)]
+pub struct DetectQuery<'q> {
+    /// 待识别的文本，长度需要低于 2000。
+    #[serde(rename = "Text")]
+    pub text:      &'q str,
+    #[serde(rename = "ProjectId")]
+    pub projectid: u8,
+}
+
+impl<'q> DetectQuery<'q> {
+    #[rustfmt::skip]
+    pub fn new(text: &'q str, projectid: u8) -> Self { Self { text, projectid } }
+}
+
+/// TMT 接口：请求体及其对应的 `X-TC-Action`。[`Header`] 以相同的方式为它们签名。
+pub trait Action: Serialize {
+    const ACTION: &'static str;
+}
+
+impl Action for Query<'_> {
+    const ACTION: &'static str = "TextTranslateBatch";
+}
+
+impl Action for TextQuery<'_> {
+    const ACTION: &'static str = "TextTranslate";
+}
+
+impl Action for DetectQuery<'_> {
+    const ACTION: &'static str = "LanguageDetect";
+}
+
 /// 账户信息以及一些不变的信息
 /// 需要：机器翻译（TMT）全读写访问权限
 #[derive(Debug, Deserialize)]
@@ -100,9 +173,10 @@ pub struct User {
     pub id:        String,
     /// SecretKey
     pub key:       String,
-    /// 地域列表，默认为北京。
+    /// 地域，比如 `ap-guangzhou`。设置后请求该地域的接入点
+    /// `tmt.<region>.tencentcloudapi.com`；不设置时就近接入，并以北京地域签名。
     #[serde(default)]
-    pub region:    Region,
+    pub region:    Option<Region>,
     /// 项目ID，可以根据控制台-账号中心-项目管理中的配置填写，如无配置请填写默认项目ID:0
     #[serde(default = "default_projectid")]
     pub projectid: u8,
@@ -114,6 +188,100 @@ pub struct User {
     #[serde(default = "default_limit")]
     // #[serde(skip_deserializing)]
     pub limit: Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:     Retry,
+    /// 请求地址，优先于 `region` 的接入点。签名中的 host 会随之改变。
+    #[serde(default)]
+    pub endpoint:  Option<String>,
+    /// 临时密钥的 Token：设置后以 `X-TC-Token` 请求头提交，此时 id 和 key 为临时密钥。
+    #[serde(default)]
+    pub token:     Option<String>,
+    /// 临时密钥的来源：在临时密钥过期之前重新读取 id、key 和 token。
+    #[serde(default)]
+    pub refresh:   Option<Refresh>,
+    /// 术语：只使用 [`Glossary::untranslated`]。
+    #[serde(default)]
+    pub glossary:  Glossary,
+    /// 服务器时间减去本地时间（秒），见 [`User::clock`]。
+    #[serde(skip)]
+    pub offset:    AtomicI64,
+}
+
+/// 临时密钥的来源，比如 `refresh = { file = "sts.json" }` 或者
+/// `refresh = { command = ["tccli", "sts", "GetFederationToken", ...] }`。
+/// 文件内容或者命令的标准输出为 [`Credential`] 的 JSON。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Refresh {
+    File(PathBuf),
+    /// 程序及其参数
+    Command(Vec<String>),
+}
+
+/// 临时密钥
+///
+/// 接受两种 JSON：
+/// - `{"TmpSecretId": "...", "TmpSecretKey": "...", "Token": "...", "ExpiredTime":
+///   1700000000}`
+/// - STS 接口（比如 GetFederationToken）的返回值：`{"Credentials": {"TmpSecretId": ...,
+///   "TmpSecretKey": ..., "Token": ...}, "ExpiredTime": 1700000000, ...}`，也可以再包一层
+///   `{"Response": ...}`
+///
+/// https://cloud.tencent.com/document/product/1312/48195
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "CredentialJson")]
+pub struct Credential {
+    pub id:      String,
+    pub key:     String,
+    pub token:   String,
+    /// 过期时间（Unix 时间戳，单位为秒）；未知时为 None
+    pub expired: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct Tmp {
+    #[serde(rename = "TmpSecretId")]
+    id:    String,
+    #[serde(rename = "TmpSecretKey")]
+    key:   String,
+    #[serde(rename = "Token")]
+    token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CredentialJson {
+    Flat {
+        #[serde(flatten)]
+        tmp:     Tmp,
+        #[serde(rename = "ExpiredTime")]
+        expired: Option<i64>,
+    },
+    Sts {
+        #[serde(rename = "Credentials")]
+        tmp:     Tmp,
+        #[serde(rename = "ExpiredTime")]
+        expired: Option<i64>,
+    },
+    Response {
+        #[serde(rename = "Response")]
+        response: Box<CredentialJson>,
+    },
+}
+
+impl From<CredentialJson> for Credential {
+    fn from(json: CredentialJson) -> Self {
+        match json {
+            CredentialJson::Flat { tmp, expired } | CredentialJson::Sts { tmp, expired } => {
+                Self { id: tmp.id,
+                       key: tmp.key,
+                       token: tmp.token,
+                       expired }
+            }
+            CredentialJson::Response { response } => (*response).into(),
+        }
+    }
 }
 
 fn default_qps() -> u8 { 5 }
@@ -124,81 +292,122 @@ impl Default for User {
     fn default() -> Self {
         Self { id:        String::new(),
                key:       String::new(),
-               region:    Region::default(),
+               region:    None,
                projectid: 0,
                qps:       5,
-               limit:     default_limit(), }
+               limit:     default_limit(),
+               retry:     Retry::default(),
+               endpoint:  None,
+               token:     None,
+               refresh:   None,
+               glossary:  Glossary::default(),
+               offset:    AtomicI64::new(0), }
+    }
+}
+
+impl User {
+    /// 请求地址：依次为 `endpoint`、`region` 的接入点、[`URL`]。
+    pub fn url(&self) -> Cow<'_, str> {
+        match (&self.endpoint, &self.region) {
+            (Some(endpoint), _) => endpoint.into(),
+            (None, Some(region)) => format!("https://{}", region.host()).into(),
+            (None, None) => URL.into(),
+        }
+    }
+
+    /// 请求地址中的 host 部分（含端口），用于签名和请求头。与 [`User::url`] 一致。
+    pub fn host(&self) -> Cow<'_, str> {
+        match (&self.endpoint, &self.region) {
+            (Some(url), _) => {
+                let url = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+                url.split('/').next().unwrap_or(url).into()
+            }
+            (None, Some(region)) => region.host().into(),
+            (None, None) => HOST.into(),
+        }
+    }
+
+    /// 使用新的临时密钥
+    pub fn set_credential(&mut self, credential: Credential) {
+        self.id = credential.id;
+        self.key = credential.key;
+        self.token = Some(credential.token);
+    }
+
+    /// 段落中需要保护的不翻译文本：取 [`Glossary::untranslated`] 中最先列出、且出现在段落中的
+    /// 那一个。
+    pub fn untranslated(&self, q: &str) -> Option<&str> {
+        self.glossary.untranslated.iter().map(String::as_str).find(|t| q.contains(t))
     }
+
+    /// 签名所用的时钟：本地时间加上 [`offset`][`User::offset`]。偏移量由调用方根据签名过期的
+    /// 响应校正，只影响这个帐号和接入点的请求。
+    pub fn clock(&self) -> SkewedClock {
+        SkewedClock { offset: self.offset.load(Ordering::Relaxed) }
+    }
+
+    /// `X-TC-Region` 的取值：未设置地域时为北京。
+    pub fn region(&self) -> &str { self.region.as_ref().map_or("ap-beijing", Region::as_str) }
 }
 
-/// 生成请求结构
+/// 生成请求结构：默认为翻译（[`Query`]）的请求，也可以是其他 [`Action`]。
 #[derive(Debug)]
-pub struct Header<'u, 'q> {
+pub struct Header<'u, 'q, Q = Query<'q>> {
     pub datetime:         OffsetDateTime,
     pub timestamp:        String,
     pub credential_scope: String,
     pub authorization:    String,
     pub user:             &'u User,
-    pub query:            &'q Query<'q>,
+    pub query:            &'q Q,
+    /// 签名和请求头共用的 host，见 [`User::host`]
+    pub host:             Cow<'u, str>,
 }
 
-impl<'u, 'q> Header<'u, 'q> {
-    const ACTION: &'static str = "TextTranslateBatch";
-    const ALGORITHM: &'static str = "TC3-HMAC-SHA256";
-    const CANONICALHEADERS: &'static str =
-        "content-type:application/json\nhost:tmt.tencentcloudapi.com\n";
-    // const CANONICALHEADERS: &'static str =
-    //     "content-type:application/json; charset=utf-8\nhost:cvm.tencentcloudapi.com\n";
-    const CANONICALQUERYSTRING: &'static str = "";
-    const CANONICALURI: &'static str = "/";
+impl<'u, 'q, Q: Action> Header<'u, 'q, Q> {
     const CONTENTTYPE: &'static str = "application/json";
     // const CONTENTTYPE: &'static str = "application/json; charset=utf-8";
-    const CREDENTIALSCOPE: &'static str = "tc3_request";
-    const HOST: &'static str = "tmt.tencentcloudapi.com";
-    const HTTPREQUESTMETHOD: &'static str = "POST";
     const SERVICE: &'static str = "tmt";
-    const SIGNEDHEADERS: &'static str = "content-type;host";
     const VERSION: &'static str = "2018-03-21";
 
+    pub fn new(user: &'u User, query: &'q Q) -> Self { Self::with_clock(user, query, &SystemClock) }
+
+    /// 以 `clock` 的当前时间生成请求结构：测试时可以固定时间。
     #[rustfmt::skip]
-    pub fn new(user: &'u User, query: &'q Query) -> Self {
-        let datetime = OffsetDateTime::now_utc();
+    pub fn with_clock(user: &'u User, query: &'q Q, clock: &impl Clock) -> Self {
+        let datetime = clock.now();
         let timestamp = datetime.unix_timestamp().to_string();
         Self { datetime, timestamp, credential_scope: String::new(),
-               authorization: String::new(), user, query }
+               authorization: String::new(), user, query, host: user.host() }
     }
 
+    /// 这次请求的签名信息：TMT 服务，接口由 [`Action`] 决定。
+    pub fn signer(&self) -> Signer<'_> {
+        Signer { id:           &self.user.id,
+                 key:          &self.user.key,
+                 service:      Self::SERVICE,
+                 host:         &self.host,
+                 action:       Q::ACTION,
+                 content_type: Self::CONTENTTYPE,
+                 sign_action:  false,
+                 datetime:     self.datetime, }
+    }
+
+    /// 参与签名的请求头：content-type 和 host。host 取决于 [`User::host`]。
+    pub fn canonical_headers(&self) -> String { self.signer().canonical_headers() }
+
     pub fn signature(&mut self) -> Result<String> {
-        let canonical_request = format!("{}\n{}\n{}\n{}\n{}\n{}",
-                                        Self::HTTPREQUESTMETHOD,
-                                        Self::CANONICALURI,
-                                        Self::CANONICALQUERYSTRING,
-                                        Self::CANONICALHEADERS,
-                                        Self::SIGNEDHEADERS,
-                                        self.query.to_hashed()?);
-
-        let date = self.datetime.date();
-        self.credential_scope = format!("{}/{}/{}", date, Self::SERVICE, Self::CREDENTIALSCOPE);
-        let stringtosign = format!("{}\n{}\n{}\n{}",
-                                   Self::ALGORITHM,
-                                   self.timestamp,
-                                   self.credential_scope,
-                                   hash256(canonical_request.as_bytes()));
-        let secret_date =
-            hash_2u8(format!("TC3{}", self.user.key).as_bytes(), format!("{date}").as_bytes())?;
-        let secret_service = hash_hash_u8(secret_date, Self::SERVICE.as_bytes())?;
-        let secret_signing = hash_hash_u8(secret_service, Self::CREDENTIALSCOPE.as_bytes())?;
-        Ok(hmac_sha256_string(hash_hash_u8(secret_signing, stringtosign.as_bytes())?))
+        let payload = serde_json::to_vec(self.query)?;
+        self.credential_scope = self.signer().credential_scope();
+        self.signer().signature(&payload)
     }
 
     pub fn authorization(&mut self) -> Result<&str> {
-        let signature = self.signature()?;
-        self.authorization = format!("{} Credential={}/{}, SignedHeaders={}, Signature={}",
-                                     Self::ALGORITHM,
-                                     self.user.id,
-                                     self.credential_scope,
-                                     Self::SIGNEDHEADERS,
-                                     signature);
+        let payload = serde_json::to_vec(self.query)?;
+        let signer = self.signer();
+        let (credential_scope, authorization) =
+            (signer.credential_scope(), signer.authorization(&payload)?);
+        self.credential_scope = credential_scope;
+        self.authorization = authorization;
         Ok(&self.authorization)
     }
 
@@ -206,11 +415,81 @@ impl<'u, 'q> Header<'u, 'q> {
         let mut map = HashMap::with_capacity(8);
         map.insert("authorization", self.authorization.as_str()).unwrap_or_default();
         map.insert("content-type", Self::CONTENTTYPE).unwrap_or_default();
-        map.insert("host", Self::HOST).unwrap_or_default();
-        map.insert("x-tc-action", Self::ACTION).unwrap_or_default();
+        map.insert("host", &self.host).unwrap_or_default();
+        map.insert("x-tc-action", Q::ACTION).unwrap_or_default();
         map.insert("x-tc-version", Self::VERSION).unwrap_or_default();
-        map.insert("x-tc-region", self.user.region.as_str()).unwrap_or_default();
+        map.insert("x-tc-region", self.user.region()).unwrap_or_default();
         map.insert("x-tc-timestamp", &self.timestamp).unwrap_or_default();
+        if let Some(token) = &self.user.token {
+            map.insert("x-tc-token", token).unwrap_or_default();
+        }
         map
     }
 }
+
+#[test]
+fn endpoint_host_test() {
+    let mut user = User::default();
+    let query = Query::new(&["hi"], "en", "zh", 0);
+    assert_eq!(user.url(), URL);
+    assert_eq!(Header::new(&user, &query).canonical_headers(),
+               "content-type:application/json\nhost:tmt.tencentcloudapi.com\n");
+
+    user.endpoint = Some("http://127.0.0.1:8080/tmt".into());
+    let header = Header::new(&user, &query);
+    assert_eq!(header.canonical_headers(), "content-type:application/json\nhost:127.0.0.1:8080\n");
+    assert_eq!(header.header()["host"], "127.0.0.1:8080");
+
+    user.region = Some(Region::Frankfurt);
+    let header = Header::new(&user, &query);
+    assert_eq!(header.header()["host"], "127.0.0.1:8080");
+    assert_eq!(header.header()["x-tc-region"], "eu-frankfurt");
+
+    user.endpoint = None;
+    assert_eq!(user.url(), "https://tmt.eu-frankfurt.tencentcloudapi.com");
+    let header = Header::new(&user, &query);
+    assert_eq!(header.canonical_headers(),
+               "content-type:application/json\nhost:tmt.eu-frankfurt.tencentcloudapi.com\n");
+    assert_eq!(header.header()["host"], "tmt.eu-frankfurt.tencentcloudapi.com");
+
+    assert!(!header.header().contains_key("x-tc-token"));
+
+    let datetime = OffsetDateTime::from_unix_timestamp(1636111645).unwrap();
+    let mut header = Header::with_clock(&user, &query, &datetime);
+    assert!(header.authorization().unwrap().contains("/2021-11-05/tmt/tc3_request, "));
+    assert_eq!(header.header()["x-tc-timestamp"], "1636111645");
+
+    let mut text = TextQuery::new("hi", "en", "zh", 0);
+    assert_eq!(Header::new(&user, &text).header()["x-tc-action"], "TextTranslate");
+    assert_eq!(serde_json::to_string(&text).unwrap(),
+               r#"{"SourceText":"hi","Source":"en","Target":"zh","ProjectId":0}"#);
+    user.glossary.untranslated = vec!["Rust".into(), "bilingual".into()];
+    text.q = "bilingual is written in Rust";
+    text.untranslated = user.untranslated(text.q);
+    assert_eq!(text.untranslated, Some("Rust"));
+    assert_eq!(user.untranslated("hi"), None);
+
+    let detect = DetectQuery::new("hi", 0);
+    assert_eq!(Header::new(&user, &detect).header()["x-tc-action"], "LanguageDetect");
+    assert_eq!(serde_json::to_string(&detect).unwrap(), r#"{"Text":"hi","ProjectId":0}"#);
+}
+
+#[test]
+fn credential_test() {
+    let flat =
+        r#"{"TmpSecretId":"AKID","TmpSecretKey":"KEY","Token":"TOKEN","ExpiredTime":1700000000}"#;
+    let credential = Credential { id:      "AKID".into(),
+                                  key:     "KEY".into(),
+                                  token:   "TOKEN".into(),
+                                  expired: Some(1700000000), };
+    assert_eq!(serde_json::from_str::<Credential>(flat).unwrap(), credential);
+    let sts = r#"{"Response":{"Credentials":{"Token":"TOKEN","TmpSecretId":"AKID","TmpSecretKey":"KEY"},"ExpiredTime":1700000000,"Expiration":"2023-11-14T22:13:20Z","RequestId":"1"}}"#;
+    assert_eq!(serde_json::from_str::<Credential>(sts).unwrap(), credential);
+
+    let mut user: User =
+        serde_json::from_str(r#"{"id":"","key":"","refresh":{"file":"sts.json"}}"#).unwrap();
+    assert!(matches!(user.refresh, Some(Refresh::File(_))));
+    user.set_credential(credential);
+    let query = Query::new(&["hi"], "en", "zh", 0);
+    assert_eq!(Header::new(&user, &query).header()["x-tc-token"], "TOKEN");
+}
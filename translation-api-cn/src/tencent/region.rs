@@ -17,47 +17,33 @@
 /// | 美国西部(硅谷) | na-siliconvalley |
 /// | 北美地区(多伦多) | na-toronto |
 ///
+/// 不在列表中的地域（比如新开放的地域）以 [`Region::Other`] 原样保存。
+///
 /// ## 注意
 /// 金融区需要单独申请，而且只为金融客户服务。具体见：
 /// https://cloud.tencent.com/document/product/304/2766
-#[derive(Debug, serde:: Deserialize, serde:: Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum Region {
-    #[serde(rename = "ap-beijing")]
+    #[default]
     Beijing,
-    #[serde(rename = "ap-shanghai")]
     Shanghai,
-    #[serde(rename = "ap-shanghai-fsi")]
     ShanghaiFsi,
-    #[serde(rename = "ap-guangzhou")]
     Guangzhou,
-    #[serde(rename = "ap-shenzhen-fsi")]
     ShenzhenFsi,
-    #[serde(rename = "ap-chengdu")]
     Chengdu,
-    #[serde(rename = "ap-chongqing")]
     Chongqing,
-    #[serde(rename = "ap-hongkong")]
     Hongkong,
-    #[serde(rename = "ap-bangkok")]
     Bangkok,
-    #[serde(rename = "ap-mumbai")]
     Mumbai,
-    #[serde(rename = "ap-seoul")]
     Seoul,
-    #[serde(rename = "ap-singapore")]
     Singapore,
-    #[serde(rename = "ap-frankfurt")]
     Frankfurt,
-    #[serde(rename = "ap-ashburn")]
     Ashburn,
-    #[serde(rename = "ap-siliconvalley")]
     Siliconvalley,
-    #[serde(rename = "ap-toronto")]
     Toronto,
-}
-
-impl Default for Region {
-    fn default() -> Self { Self::Beijing }
+    /// 其他地域的取值
+    Other(String),
 }
 
 impl Region {
@@ -77,10 +63,74 @@ impl Region {
             Mumbai        => "ap-mumbai",
             Seoul         => "ap-seoul",
             Singapore     => "ap-singapore",
-            Frankfurt     => "ap-frankfurt",
-            Ashburn       => "ap-ashburn",
-            Siliconvalley => "ap-siliconvalley",
-            Toronto       => "ap-toronto",
+            Frankfurt     => "eu-frankfurt",
+            Ashburn       => "na-ashburn",
+            Siliconvalley => "na-siliconvalley",
+            Toronto       => "na-toronto",
+            Other(s)      => s,
+        }
+    }
+
+    /// 该地域的接入点，比如 `tmt.ap-guangzhou.tencentcloudapi.com`
+    pub fn host(&self) -> String { format!("tmt.{}.tencentcloudapi.com", self.as_str()) }
+}
+
+impl From<&str> for Region {
+    #[rustfmt::skip]
+    fn from(s: &str) -> Self {
+        use Region::*;
+        match s {
+            "ap-beijing"       => Beijing,
+            "ap-shanghai"      => Shanghai,
+            "ap-shanghai-fsi"  => ShanghaiFsi,
+            "ap-guangzhou"     => Guangzhou,
+            "ap-shenzhen-fsi"  => ShenzhenFsi,
+            "ap-chengdu"       => Chengdu,
+            "ap-chongqing"     => Chongqing,
+            "ap-hongkong"      => Hongkong,
+            "ap-bangkok"       => Bangkok,
+            "ap-mumbai"        => Mumbai,
+            "ap-seoul"         => Seoul,
+            "ap-singapore"     => Singapore,
+            "eu-frankfurt"     => Frankfurt,
+            "na-ashburn"       => Ashburn,
+            "na-siliconvalley" => Siliconvalley,
+            "na-toronto"       => Toronto,
+            other              => Other(other.into()),
         }
     }
 }
+
+impl From<String> for Region {
+    fn from(s: String) -> Self {
+        match Region::from(s.as_str()) {
+            Region::Other(_) => Region::Other(s),
+            region => region,
+        }
+    }
+}
+
+impl From<Region> for String {
+    fn from(region: Region) -> Self {
+        match region {
+            Region::Other(s) => s,
+            region => region.as_str().into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[test]
+fn region_test() {
+    let regions: Vec<Region> =
+        serde_json::from_str(r#"["eu-frankfurt", "na-toronto", "ap-jakarta"]"#).unwrap();
+    assert_eq!(regions, [Region::Frankfurt, Region::Toronto, Region::Other("ap-jakarta".into())]);
+    assert_eq!(serde_json::to_string(&regions).unwrap(),
+               r#"["eu-frankfurt","na-toronto","ap-jakarta"]"#);
+    assert_eq!(Region::Siliconvalley.host(), "tmt.na-siliconvalley.tencentcloudapi.com");
+}
@@ -36,6 +36,78 @@ impl<'r> Response<'r> {
     }
 }
 
+/// TextTranslate 的响应
+#[derive(Debug, Deserialize)]
+pub struct TextResponse<'r> {
+    #[serde(borrow)]
+    #[serde(rename = "Response")]
+    pub res: TextInner<'r>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TextInner<'r> {
+    Ok {
+        #[serde(rename = "RequestId")]
+        id:  &'r str,
+        #[serde(borrow)]
+        #[serde(rename = "TargetText")]
+        res: Cow<'r, str>,
+    },
+    Err {
+        #[serde(rename = "RequestId")]
+        id:    &'r str,
+        #[serde(rename = "Error")]
+        error: ResponseError,
+    },
+}
+
+impl<'r> TextResponse<'r> {
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> Result<String, ResponseError> {
+        match self.res {
+            TextInner::Ok { res, .. } => Ok(res.into_owned()),
+            TextInner::Err { error, .. } => Err(error),
+        }
+    }
+}
+
+/// LanguageDetect 的响应
+#[derive(Debug, Deserialize)]
+pub struct DetectResponse<'r> {
+    #[serde(borrow)]
+    #[serde(rename = "Response")]
+    pub res: DetectInner<'r>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum DetectInner<'r> {
+    Ok {
+        #[serde(rename = "RequestId")]
+        id:   &'r str,
+        /// 识别出的语言，比如 `zh`、`en`、`ja`
+        #[serde(rename = "Lang")]
+        lang: &'r str,
+    },
+    Err {
+        #[serde(rename = "RequestId")]
+        id:    &'r str,
+        #[serde(rename = "Error")]
+        error: ResponseError,
+    },
+}
+
+impl<'r> DetectResponse<'r> {
+    /// 识别出的语言
+    pub fn lang(&self) -> Result<&str, ResponseError> {
+        match &self.res {
+            DetectInner::Ok { lang, .. } => Ok(lang),
+            DetectInner::Err { error, .. } => Err(error.clone()),
+        }
+    }
+}
+
 /// 响应的信息。要么返回翻译结果，要么返回错误信息。
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
@@ -165,6 +237,33 @@ impl ResponseError {
             _ => "未知错误。",
         }
     }
+
+    /// 是否为暂时性的错误：请求频率受限、内部错误或者服务暂时不可用。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool {
+        let code = self.code.as_str();
+        code.starts_with("RequestLimitExceeded")
+        || code.starts_with("InternalError")
+        || code == "LimitExceeded.LimitedAccessFrequency"
+        || code == "ServiceUnavailable"
+    }
+
+    /// 签名过期：本地时间与服务器时间相差超过五分钟
+    pub fn is_signature_expire(&self) -> bool { self.code == "AuthFailure.SignatureExpire" }
+
+    /// 从任一接口的响应中读取错误信息；请求成功或者无法解析时返回 None。
+    pub fn from_response(bytes: &[u8]) -> Option<ResponseError> {
+        #[derive(Deserialize)]
+        struct Outer {
+            #[serde(rename = "Response")]
+            res: Inner,
+        }
+        #[derive(Deserialize)]
+        struct Inner {
+            #[serde(rename = "Error")]
+            error: Option<ResponseError>,
+        }
+        serde_json::from_slice::<Outer>(bytes).ok()?.res.error
+    }
 }
 
 #[test]
@@ -205,5 +304,21 @@ fn response_test() -> Result<(), Box<dyn std::error::Error>> {
     assert!(res.dst().is_err());
     // dbg!(res.dst()?); // this error leads to panic
 
+    let error = r#"{"Response":{"Error":{"Code":"RequestLimitExceeded.UinLimitExceeded","Message":"Your request exceeds the frequency limit."},"RequestId":"2c3d2c8b-e1a4-4e6f-9b1a-1f3cfd8c3d0a"}}"#;
+    let res: Response = serde_json::from_str(error)?;
+    assert!(res.dst_owned().unwrap_err().is_retriable());
+    assert!(ResponseError::from_response(error.as_bytes()).is_some_and(|e| e.is_retriable()));
+    assert!(ResponseError::from_response(success.as_bytes()).is_none());
+
+    let expire = r#"{"Response":{"Error":{"Code":"AuthFailure.SignatureExpire","Message":"签名过期"},"RequestId":"1"}}"#;
+    assert!(ResponseError::from_response(expire.as_bytes()).unwrap().is_signature_expire());
+
+    let text = r#"{"Response":{"TargetText":"bilingual 是用 Rust 写的","Source":"en","Target":"zh","RequestId":"2"}}"#;
+    assert_eq!(serde_json::from_str::<TextResponse>(text)?.dst_owned()?,
+               "bilingual 是用 Rust 写的");
+
+    let detect = r#"{"Response":{"Lang":"ja","RequestId":"1d8b3a4f-6c2e-4b1a-9a57-0e1c4d6f2b3a"}}"#;
+    assert_eq!(serde_json::from_str::<DetectResponse>(detect)?.lang()?, "ja");
+
     Ok(())
 }
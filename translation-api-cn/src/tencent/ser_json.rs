@@ -99,29 +99,31 @@ fn signature_to_string_test() -> super::Result<()> {
                         projectid: 0,
                         q:         &["hi", "there"], };
     // sample ends
+    let mut header = Header { datetime,
+                              timestamp,
+                              credential_scope: "".into(),
+                              authorization: "".into(),
+                              user: &user,
+                              query: &query,
+                              host: user.host() };
     let canonical_request = format!("{}\n{}\n{}\n{}\n{}\n{}",
-                                    Header::HTTPREQUESTMETHOD,
-                                    Header::CANONICALURI,
-                                    Header::CANONICALQUERYSTRING,
-                                    Header::CANONICALHEADERS,
-                                    Header::SIGNEDHEADERS,
+                                    Signer::HTTPREQUESTMETHOD,
+                                    Signer::CANONICALURI,
+                                    Signer::CANONICALQUERYSTRING,
+                                    header.canonical_headers(),
+                                    header.signer().signed_headers(),
                                     query.to_hashed2()?);
     #[rustfmt::skip]
     assert_eq!(canonical_request,
                "POST\n/\n\ncontent-type:application/json\n\
                 host:tmt.tencentcloudapi.com\n\ncontent-type;host\n\
                 132203170c4d03f4b351cacc51a7ceeed78ca571be42688945f74bb0796bb739");
-    let mut header = Header { datetime,
-                              timestamp,
-                              credential_scope: "".into(),
-                              authorization: "".into(),
-                              user: &user,
-                              query: &query };
     let date = datetime.date();
-    header.credential_scope = format!("{}/{}/{}", date, Header::SERVICE, Header::CREDENTIALSCOPE);
+    header.credential_scope =
+        format!("{}/{}/{}", date, Header::<Query>::SERVICE, Signer::CREDENTIALSCOPE);
     assert_eq!(header.credential_scope, "2021-11-05/tmt/tc3_request");
     let stringtosign = format!("{}\n{}\n{}\n{}",
-                               Header::ALGORITHM,
+                               Signer::ALGORITHM,
                                header.timestamp,
                                header.credential_scope,
                                hash256(canonical_request.as_bytes()));
@@ -130,8 +132,8 @@ fn signature_to_string_test() -> super::Result<()> {
                               ef9234630cfbd7baf254265506ed5d0193d278468d367a9c8a809d6300173df1");
     let secret_date =
         hash_2u8(format!("TC3{}", header.user.key).as_bytes(), format!("{date}").as_bytes())?;
-    let secret_service = hash_hash_u8(secret_date, Header::SERVICE.as_bytes())?;
-    let secret_signing = hash_hash_u8(secret_service, Header::CREDENTIALSCOPE.as_bytes())?;
+    let secret_service = hash_hash_u8(secret_date, Header::<Query>::SERVICE.as_bytes())?;
+    let secret_signing = hash_hash_u8(secret_service, Signer::CREDENTIALSCOPE.as_bytes())?;
     let hex = hmac_sha256_string(hash_hash_u8(secret_signing, stringtosign.as_bytes())?);
     assert_eq!(hex, "5a4474831e97a0b0e37730abf8de690234fb750be49bf5033469f2b626752eb5");
     Ok(())
@@ -0,0 +1,230 @@
+//! TC3-HMAC-SHA256 签名
+//!
+//! 腾讯云 API 3.0 的各个接口（TextTranslate、TextTranslateBatch、LanguageDetect、FileTranslate
+//! 等）使用同样的签名方法，区别只在于服务、接口名、版本、地域和请求体。
+//!
+//! https://cloud.tencent.com/document/api/551/30636
+use super::{hash256, hash_2u8, hash_hash_u8, hmac_sha256_string, Result};
+use time::{Date, Duration, Month, OffsetDateTime, Time};
+
+/// 签名所使用的时间。测试时可以使用固定的时间（[`OffsetDateTime`] 本身就是固定的时钟）。
+pub trait Clock {
+    fn now(&self) -> OffsetDateTime;
+}
+
+/// 系统时间（UTC）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime { OffsetDateTime::now_utc() }
+}
+
+impl Clock for OffsetDateTime {
+    fn now(&self) -> OffsetDateTime { *self }
+}
+
+/// 系统时间加上偏移量：本地时钟与服务器时间相差较大时，以服务器时间签名。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SkewedClock {
+    /// 服务器时间减去本地时间，单位为秒
+    pub offset: i64,
+}
+
+impl Clock for SkewedClock {
+    fn now(&self) -> OffsetDateTime { OffsetDateTime::now_utc() + Duration::seconds(self.offset) }
+}
+
+impl SkewedClock {
+    /// 根据响应的 `Date` 头（服务器时间）计算偏移量。
+    pub fn from_date(date: &str) -> Option<Self> {
+        let offset = (http_date(date)? - OffsetDateTime::now_utc()).whole_seconds();
+        Some(Self { offset })
+    }
+}
+
+/// 解析 HTTP 的日期格式，比如 `Mon, 25 Feb 2019 08:44:25 GMT`
+pub fn http_date(s: &str) -> Option<OffsetDateTime> {
+    let mut parts = s.split_whitespace().skip(1);
+    let day = parts.next()?.parse().ok()?;
+    let month = match parts.next()? {
+        "Jan" => Month::January,
+        "Feb" => Month::February,
+        "Mar" => Month::March,
+        "Apr" => Month::April,
+        "May" => Month::May,
+        "Jun" => Month::June,
+        "Jul" => Month::July,
+        "Aug" => Month::August,
+        "Sep" => Month::September,
+        "Oct" => Month::October,
+        "Nov" => Month::November,
+        "Dec" => Month::December,
+        _ => return None,
+    };
+    let year = parts.next()?.parse().ok()?;
+    let mut hms = parts.next()?.splitn(3, ':').map(str::parse::<u8>);
+    let (h, m, sec) = (hms.next()?.ok()?, hms.next()?.ok()?, hms.next()?.ok()?);
+    if parts.next()? != "GMT" {
+        return None;
+    }
+    let date = Date::from_calendar_date(year, month, day).ok()?;
+    Some(date.with_time(Time::from_hms(h, m, sec).ok()?).assume_utc())
+}
+
+/// 一次请求的签名信息：对请求体 `payload` 生成 `Authorization` 请求头。
+///
+/// 只支持 POST 请求，参与签名的请求头为 content-type、host，以及可选的 x-tc-action。
+#[derive(Debug, Clone)]
+pub struct Signer<'s> {
+    /// SecretId
+    pub id:           &'s str,
+    /// SecretKey
+    pub key:          &'s str,
+    /// 服务，比如 `tmt`
+    pub service:      &'s str,
+    /// 请求的 host，比如 `tmt.tencentcloudapi.com`
+    pub host:         &'s str,
+    /// 接口名，比如 `TextTranslateBatch`
+    pub action:       &'s str,
+    pub content_type: &'s str,
+    /// 是否把 `X-TC-Action` 请求头纳入签名
+    pub sign_action:  bool,
+    /// 请求时间：时间戳和签名中的日期都由它得出
+    pub datetime:     OffsetDateTime,
+}
+
+impl<'s> Signer<'s> {
+    pub const ALGORITHM: &'static str = "TC3-HMAC-SHA256";
+    pub const CANONICALQUERYSTRING: &'static str = "";
+    pub const CANONICALURI: &'static str = "/";
+    pub const CREDENTIALSCOPE: &'static str = "tc3_request";
+    pub const HTTPREQUESTMETHOD: &'static str = "POST";
+
+    /// 使用 `clock` 的当前时间，其余字段为空
+    pub fn with_clock(clock: &impl Clock) -> Self {
+        Self { id:           "",
+               key:          "",
+               service:      "",
+               host:         "",
+               action:       "",
+               content_type: "application/json",
+               sign_action:  false,
+               datetime:     clock.now(), }
+    }
+
+    /// `X-TC-Timestamp` 的取值
+    pub fn timestamp(&self) -> i64 { self.datetime.unix_timestamp() }
+
+    /// 参与签名的请求头名称
+    pub fn signed_headers(&self) -> &'static str {
+        if self.sign_action {
+            "content-type;host;x-tc-action"
+        } else {
+            "content-type;host"
+        }
+    }
+
+    pub fn canonical_headers(&self) -> String {
+        let mut headers = format!("content-type:{}\nhost:{}\n", self.content_type, self.host);
+        if self.sign_action {
+            headers.push_str("x-tc-action:");
+            headers.push_str(&self.action.to_ascii_lowercase());
+            headers.push('\n');
+        }
+        headers
+    }
+
+    pub fn canonical_request(&self, payload: &[u8]) -> String {
+        format!("{}\n{}\n{}\n{}\n{}\n{}",
+                Self::HTTPREQUESTMETHOD,
+                Self::CANONICALURI,
+                Self::CANONICALQUERYSTRING,
+                self.canonical_headers(),
+                self.signed_headers(),
+                hash256(payload))
+    }
+
+    /// 形如 `2021-11-05/tmt/tc3_request`：日期为 UTC 日期
+    pub fn credential_scope(&self) -> String {
+        format!("{}/{}/{}", self.datetime.date(), self.service, Self::CREDENTIALSCOPE)
+    }
+
+    pub fn string_to_sign(&self, payload: &[u8]) -> String {
+        format!("{}\n{}\n{}\n{}",
+                Self::ALGORITHM,
+                self.timestamp(),
+                self.credential_scope(),
+                hash256(self.canonical_request(payload).as_bytes()))
+    }
+
+    pub fn signature(&self, payload: &[u8]) -> Result<String> {
+        let date = self.datetime.date().to_string();
+        let secret_date = hash_2u8(format!("TC3{}", self.key).as_bytes(), date.as_bytes())?;
+        let secret_service = hash_hash_u8(secret_date, self.service.as_bytes())?;
+        let secret_signing = hash_hash_u8(secret_service, Self::CREDENTIALSCOPE.as_bytes())?;
+        let stringtosign = self.string_to_sign(payload);
+        Ok(hmac_sha256_string(hash_hash_u8(secret_signing, stringtosign.as_bytes())?))
+    }
+
+    /// `Authorization` 请求头的取值
+    pub fn authorization(&self, payload: &[u8]) -> Result<String> {
+        Ok(format!("{} Credential={}/{}, SignedHeaders={}, Signature={}",
+                   Self::ALGORITHM,
+                   self.id,
+                   self.credential_scope(),
+                   self.signed_headers(),
+                   self.signature(payload)?))
+    }
+}
+
+// 官方文档中签名过程的示例（云服务器 DescribeInstances 接口）
+#[test]
+fn signer_test() -> Result<()> {
+    let datetime = OffsetDateTime::from_unix_timestamp(1551113065).unwrap();
+    let signer = Signer { id: "AKIDz8krbsJ5yKBZQpn74WFkmLPx3gnPhESA",
+                          key: "Gu5t9xGARNpq86cd98joQYCN3Cozk1qA",
+                          service: "cvm",
+                          host: "cvm.tencentcloudapi.com",
+                          action: "DescribeInstances",
+                          content_type: "application/json; charset=utf-8",
+                          sign_action: true,
+                          ..Signer::with_clock(&datetime) };
+    let payload = br#"{"Limit": 1, "Filters": [{"Values": ["\u672a\u547d\u540d"], "Name": "instance-name"}]}"#;
+    assert_eq!(hash256(payload),
+               "35e9c5b0e3ae67532d3c9f17ead6c90222632e5b1ff7f6e89887f1398934f064");
+    #[rustfmt::skip]
+    assert_eq!(signer.canonical_request(payload),
+               "POST\n/\n\ncontent-type:application/json; charset=utf-8\n\
+                host:cvm.tencentcloudapi.com\nx-tc-action:describeinstances\n\n\
+                content-type;host;x-tc-action\n\
+                35e9c5b0e3ae67532d3c9f17ead6c90222632e5b1ff7f6e89887f1398934f064");
+    #[rustfmt::skip]
+    assert_eq!(signer.string_to_sign(payload),
+               "TC3-HMAC-SHA256\n1551113065\n2019-02-25/cvm/tc3_request\n\
+                7019a55be8395899b900fb5564e4200d984910f34794a27cb3fb7d10ff6a1e84");
+    assert_eq!(signer.authorization(payload)?,
+               "TC3-HMAC-SHA256 \
+                Credential=AKIDz8krbsJ5yKBZQpn74WFkmLPx3gnPhESA/2019-02-25/cvm/tc3_request, \
+                SignedHeaders=content-type;host;x-tc-action, \
+                Signature=2220c8c846efab6e5158c3ae545e315ad80a246c20d35d53b8723eee82f2601d");
+
+    // 不签名 x-tc-action 时，与翻译接口（TextTranslateBatch）的 Header 一致
+    let signer = Signer { sign_action: false, ..signer };
+    assert_eq!(signer.signed_headers(), "content-type;host");
+    assert_eq!(signer.canonical_headers(),
+               "content-type:application/json; charset=utf-8\nhost:cvm.tencentcloudapi.com\n");
+    Ok(())
+}
+
+#[test]
+fn http_date_test() {
+    let date = http_date("Mon, 25 Feb 2019 08:44:25 GMT").unwrap();
+    assert_eq!(date.unix_timestamp(), 1551084265);
+    assert!(http_date("Mon, 25 Feb 2019 08:44:25 +0800").is_none());
+    assert!(http_date("2019-02-25T08:44:25Z").is_none());
+
+    let clock = SkewedClock { offset: -3600 };
+    let skew = OffsetDateTime::now_utc() - clock.now();
+    assert!((3599..=3601).contains(&skew.whole_seconds()));
+}
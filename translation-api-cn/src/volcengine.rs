@@ -0,0 +1,333 @@
+use crate::{
+    tencent::{hash256, hash_2u8, hash_hash_u8, hmac_sha256_string, Result},
+    Limit, Retry,
+};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::HashMap};
+use time::OffsetDateTime;
+
+pub const URL: &str = "https://translate.volcengineapi.com";
+
+/// 单次请求中 `TextList` 的最大条数
+pub const MAX_TEXT_LIST: usize = 16;
+
+/// 翻译前的必要信息
+///
+/// 参考：https://www.volcengine.com/docs/4640/65067
+#[derive(Debug, Serialize)]
+pub struct Query<'q> {
+    /// 翻译源语言。为 auto 时不传这个参数，由 API 自动检测。
+    #[serde(rename = "SourceLanguage", skip_serializing_if = "is_auto")]
+    pub from: &'q str,
+    /// 翻译目标语言
+    #[serde(rename = "TargetLanguage")]
+    pub to:   &'q str,
+    /// 请求翻译 query，最多 [`MAX_TEXT_LIST`] 条，总长度不超过 5000 字符。
+    #[serde(rename = "TextList")]
+    pub q:    &'q [&'q str],
+}
+
+fn is_auto(from: &&str) -> bool { from.is_empty() || *from == "auto" }
+
+impl<'q> Query<'q> {
+    #[rustfmt::skip]
+    pub fn new(q: &'q [&'q str], from: &'q str, to: &'q str) -> Self { Self { q, from, to } }
+
+    pub fn to_json_string(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| e.into())
+    }
+}
+
+/// 账户信息
+/// 需要：机器翻译的访问权限
+#[derive(Debug, Deserialize)]
+#[serde(rename = "volcengine")] // for config or cmd
+pub struct User {
+    /// Access Key ID
+    pub id:       String,
+    /// Secret Access Key，这个字段用于生成签名，不用于直接构造请求内容
+    pub key:      String,
+    /// 地域，默认为 cn-north-1。
+    #[serde(default = "default_region")]
+    pub region:   String,
+    /// 每秒请求数（QPS），默认为 10。
+    #[serde(default = "default_qps")]
+    pub qps:      u8,
+    /// 每次请求的字符上限，默认为 Char(5000)。
+    #[serde(default = "default_limit")]
+    pub limit:    Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:    Retry,
+    /// 请求地址，默认为 [`URL`]。签名中的 host 会随之改变。
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+fn default_region() -> String { String::from("cn-north-1") }
+fn default_qps() -> u8 { 10 }
+fn default_limit() -> Limit { Limit::Char(5000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { id:       String::new(),
+               key:      String::new(),
+               region:   default_region(),
+               qps:      default_qps(),
+               limit:    default_limit(),
+               retry:    Retry::default(),
+               endpoint: None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则为 [`URL`]。
+    pub fn url(&self) -> &str { self.endpoint.as_deref().unwrap_or(URL) }
+
+    /// 请求地址中的 host 部分（含端口），用于签名和请求头。
+    pub fn host(&self) -> &str {
+        let url = self.url();
+        let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+        url.split('/').next().unwrap_or(url)
+    }
+}
+
+/// 生成请求结构：签名方式与 AWS SigV4 类似，算法为 HMAC-SHA256。
+#[derive(Debug)]
+pub struct Header<'u> {
+    /// 形如 `20201103T104027Z` 的 UTC 时间
+    pub x_date:           String,
+    /// 请求体（JSON）
+    pub body:             String,
+    /// 请求体的 SHA-256 十六进制值
+    pub content_sha256:   String,
+    pub credential_scope: String,
+    pub authorization:    String,
+    pub user:             &'u User,
+}
+
+impl<'u> Header<'u> {
+    const ACTION: &'static str = "TranslateText";
+    const ALGORITHM: &'static str = "HMAC-SHA256";
+    const CANONICALURI: &'static str = "/";
+    const CONTENTTYPE: &'static str = "application/json";
+    const HTTPREQUESTMETHOD: &'static str = "POST";
+    const REQUEST: &'static str = "request";
+    const SERVICE: &'static str = "translate";
+    const SIGNEDHEADERS: &'static str = "content-type;host;x-content-sha256;x-date";
+    const VERSION: &'static str = "2020-06-01";
+
+    pub fn new(user: &'u User, query: &Query) -> Result<Self> {
+        Self::with_datetime(user, query, OffsetDateTime::now_utc())
+    }
+
+    /// 以指定的时间生成请求结构。
+    pub fn with_datetime(user: &'u User, query: &Query, datetime: OffsetDateTime) -> Result<Self> {
+        let body = query.to_json_string()?;
+        let content_sha256 = hash256(body.as_bytes());
+        #[rustfmt::skip]
+        let x_date = format!("{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+                             datetime.year(), u8::from(datetime.month()), datetime.day(),
+                             datetime.hour(), datetime.minute(), datetime.second());
+        Ok(Self { x_date,
+                  body,
+                  content_sha256,
+                  credential_scope: String::new(),
+                  authorization: String::new(),
+                  user })
+    }
+
+    /// 形如 `20201103` 的日期
+    pub fn short_date(&self) -> &str { &self.x_date[..8] }
+
+    pub fn canonical_query_string() -> String {
+        format!("Action={}&Version={}", Self::ACTION, Self::VERSION)
+    }
+
+    /// 包含 Action 和 Version 的请求地址
+    pub fn url(&self) -> String {
+        format!("{}/?{}", self.user.url().trim_end_matches('/'), Self::canonical_query_string())
+    }
+
+    /// 参与签名的请求头：content-type、host、x-content-sha256 和 x-date。
+    pub fn canonical_headers(&self) -> String {
+        format!("content-type:{}\nhost:{}\nx-content-sha256:{}\nx-date:{}\n",
+                Self::CONTENTTYPE,
+                self.user.host(),
+                self.content_sha256,
+                self.x_date)
+    }
+
+    pub fn signature(&mut self) -> Result<String> {
+        let canonical_request = format!("{}\n{}\n{}\n{}\n{}\n{}",
+                                        Self::HTTPREQUESTMETHOD,
+                                        Self::CANONICALURI,
+                                        Self::canonical_query_string(),
+                                        self.canonical_headers(),
+                                        Self::SIGNEDHEADERS,
+                                        self.content_sha256);
+
+        let date = self.short_date().to_owned();
+        self.credential_scope =
+            format!("{}/{}/{}/{}", date, self.user.region, Self::SERVICE, Self::REQUEST);
+        let stringtosign = format!("{}\n{}\n{}\n{}",
+                                   Self::ALGORITHM,
+                                   self.x_date,
+                                   self.credential_scope,
+                                   hash256(canonical_request.as_bytes()));
+        let secret_date = hash_2u8(self.user.key.as_bytes(), date.as_bytes())?;
+        let secret_region = hash_hash_u8(secret_date, self.user.region.as_bytes())?;
+        let secret_service = hash_hash_u8(secret_region, Self::SERVICE.as_bytes())?;
+        let secret_signing = hash_hash_u8(secret_service, Self::REQUEST.as_bytes())?;
+        Ok(hmac_sha256_string(hash_hash_u8(secret_signing, stringtosign.as_bytes())?))
+    }
+
+    pub fn authorization(&mut self) -> Result<&str> {
+        let signature = self.signature()?;
+        self.authorization = format!("{} Credential={}/{}, SignedHeaders={}, Signature={}",
+                                     Self::ALGORITHM,
+                                     self.user.id,
+                                     self.credential_scope,
+                                     Self::SIGNEDHEADERS,
+                                     signature);
+        Ok(&self.authorization)
+    }
+
+    pub fn header(&self) -> HashMap<&str, &str> {
+        let mut map = HashMap::with_capacity(8);
+        map.insert("authorization", self.authorization.as_str()).unwrap_or_default();
+        map.insert("content-type", Self::CONTENTTYPE).unwrap_or_default();
+        map.insert("host", self.user.host()).unwrap_or_default();
+        map.insert("x-content-sha256", &self.content_sha256).unwrap_or_default();
+        map.insert("x-date", &self.x_date).unwrap_or_default();
+        map
+    }
+}
+
+/// 响应的信息：出错时 `ResponseMetadata.Error` 不为空。
+#[derive(Debug, Deserialize)]
+pub struct Response<'r> {
+    #[serde(rename = "TranslationList", default, borrow)]
+    pub list:     Vec<Translation<'r>>,
+    #[serde(rename = "ResponseMetadata")]
+    pub metadata: ResponseMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Translation<'r> {
+    #[serde(rename = "Translation", borrow)]
+    pub dst: Cow<'r, str>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponseMetadata {
+    #[serde(rename = "RequestId", default)]
+    pub id:    String,
+    #[serde(rename = "Error")]
+    pub error: Option<ResponseError>,
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容。
+    pub fn dst(&self) -> std::result::Result<impl Iterator<Item = &str>, ResponseError> {
+        match &self.metadata.error {
+            Some(error) => Err(error.clone()),
+            None => Ok(self.list.iter().map(|t| t.dst.as_ref())),
+        }
+    }
+
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> std::result::Result<Vec<String>, ResponseError> {
+        match self.metadata.error {
+            Some(error) => Err(error),
+            None => Ok(self.list.into_iter().map(|t| t.dst.into()).collect()),
+        }
+    }
+}
+
+/// 错误处理 / 错误码
+///
+/// 参考：https://www.volcengine.com/docs/6369/68677
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResponseError {
+    #[serde(rename = "Code")]
+    pub code: String,
+    #[serde(rename = "Message", default)]
+    pub msg:  String,
+}
+
+impl std::error::Error for ResponseError {}
+impl std::fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,
+               "错误码：`{}`\n错误信息：`{}`\n错误含义：{}\n以上内容由火山引擎 API 返回",
+               self.code,
+               self.msg,
+               self.solution())
+    }
+}
+
+impl ResponseError {
+    /// 参考：[公共错误码](https://www.volcengine.com/docs/6369/68677)
+    pub fn solution(&self) -> &str {
+        match self.code.as_bytes() {
+            b"MissingParameter" => "缺少参数。",
+            b"InvalidParameter" => "参数错误（包括参数格式、类型等错误）。",
+            b"InvalidActionOrVersion" => "接口或者接口版本不存在。",
+            b"InvalidAccessKey" => "Access Key ID 不存在。\n解决方法：请检查 id 是否填写正确。",
+            b"SignatureDoesNotMatch" => {
+                "签名错误。\n解决方法：请检查 Secret Access Key 是否填写正确。"
+            }
+            b"InvalidTimestamp" => "签名过期。\n解决方法：请检查本地时间是否和标准时间同步。",
+            b"AccessDenied" => "没有访问权限。\n解决方法：请为子账号授予机器翻译的权限。",
+            b"ServiceNotFound" | b"ServiceUnavailable" => "服务未开通或者已停用。",
+            b"FlowLimitExceeded" => "请求的次数超过了频率限制。\n解决方法：请降低您的调用频率。",
+            b"ServiceUnavailableTemporary" => "服务暂时不可用。\n解决方法：请重试。",
+            b"InternalError" | b"InternalServiceError" => "内部错误。\n解决方法：请重试。",
+            b"InternalServiceTimeout" => "后台服务超时。\n解决方法：请重试。",
+            _ => "未知错误。",
+        }
+    }
+
+    /// 是否为暂时性的错误：请求频率受限、内部错误或者服务暂时不可用。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool {
+        matches!(self.code.as_bytes(),
+                 b"FlowLimitExceeded"
+                 | b"ServiceUnavailableTemporary"
+                 | b"InternalError"
+                 | b"InternalServiceError"
+                 | b"InternalServiceTimeout")
+    }
+}
+
+#[test]
+fn signature_test() -> Result<()> {
+    let user = User { id: "AKLTtest".into(),
+                      key: "secret".into(),
+                      ..User::default() };
+    let query = Query::new(&["hello", "world"], "auto", "zh");
+    let datetime = OffsetDateTime::from_unix_timestamp(1604400027)?;
+    let mut header = Header::with_datetime(&user, &query, datetime)?;
+    assert_eq!(header.body, r#"{"TargetLanguage":"zh","TextList":["hello","world"]}"#);
+    assert_eq!(header.x_date, "20201103T104027Z");
+    assert_eq!(header.url(),
+               "https://translate.volcengineapi.com/?Action=TranslateText&Version=2020-06-01");
+    assert_eq!(header.authorization()?,
+               "HMAC-SHA256 Credential=AKLTtest/20201103/cn-north-1/translate/request, \
+                SignedHeaders=content-type;host;x-content-sha256;x-date, \
+                Signature=7cb8478d0ad5a84492083983476238a6051e6e03a7ecacfc93ec34e63c745775");
+    Ok(())
+}
+
+#[test]
+fn response_test() {
+    let success = r#"{"TranslationList":[{"Translation":"嗨","DetectedSourceLanguage":"en","Extra":null},{"Translation":"那里","DetectedSourceLanguage":"en","Extra":null}],"ResponseMetadata":{"RequestId":"202011031040270101","Action":"TranslateText","Version":"2020-06-01","Service":"translate","Region":"cn-north-1"}}"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst().unwrap().collect::<Vec<_>>(), ["嗨", "那里"]);
+
+    let error = r#"{"ResponseMetadata":{"RequestId":"202011031040270102","Action":"TranslateText","Version":"2020-06-01","Service":"translate","Region":"cn-north-1","Error":{"CodeN":100018,"Code":"FlowLimitExceeded","Message":"Flow limit exceeded"}}}"#;
+    let res: Response = serde_json::from_str(error).unwrap();
+    let error = res.dst_owned().unwrap_err();
+    assert_eq!(error.code, "FlowLimitExceeded");
+    assert!(error.is_retriable());
+}
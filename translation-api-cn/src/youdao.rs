@@ -0,0 +1,263 @@
+use crate::{Limit, Retry};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    borrow::Cow,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+pub const URL: &str = "https://openapi.youdao.com/api";
+
+/// 翻译前的必要信息
+///
+/// 参考：https://ai.youdao.com/DOCSIRMA/html/trans/api/wbfy/index.html
+#[derive(Debug)]
+pub struct Query<'q> {
+    /// 请求翻译 query，必须为 UTF-8 编码。多个段落以 `\n` 分隔。
+    pub q:       &'q str,
+    /// 翻译源语言，可设置为 auto
+    pub from:    &'q str,
+    /// 翻译目标语言。注意简体中文为 `zh-CHS`。
+    pub to:      &'q str,
+    /// 当前 UTC 时间戳（秒）
+    pub curtime: String,
+    /// 随机字符串：同一秒内的相同请求也有不同的签名，以免被当作重放请求（错误码 207）。
+    pub salt:    String,
+    /// 应用ID + input + salt + curtime + 应用密钥的 SHA-256 值
+    pub sign:    String,
+}
+
+/// 账户信息
+#[derive(Debug, Deserialize)]
+#[serde(rename = "youdao")] // for config or cmd
+pub struct User {
+    /// 应用ID
+    pub appkey:   String,
+    /// 应用密钥，这个字段用于生成 SHA-256 ，不用于直接构造请求内容
+    pub key:      String,
+    /// 每秒请求数（QPS），默认为 1。
+    #[serde(default = "default_qps")]
+    pub qps:      u8,
+    /// 每次请求的字符上限，默认为 Char(5000)。
+    #[serde(default = "default_limit")]
+    pub limit:    Limit,
+    /// 请求失败时的重试策略。
+    #[serde(default)]
+    pub retry:    Retry,
+    /// 请求地址，默认为 [`URL`]。
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+fn default_qps() -> u8 { 1 }
+fn default_limit() -> Limit { Limit::Char(5000) }
+
+impl Default for User {
+    fn default() -> Self {
+        Self { appkey:   String::new(),
+               key:      String::new(),
+               qps:      default_qps(),
+               limit:    default_limit(),
+               retry:    Retry::default(),
+               endpoint: None, }
+    }
+}
+
+impl User {
+    /// 请求地址：配置了 `endpoint` 时使用 `endpoint`，否则为 [`URL`]。
+    pub fn url(&self) -> &str { self.endpoint.as_deref().unwrap_or(URL) }
+}
+
+impl<'q> Query<'q> {
+    /// 实例化：`curtime` 为当前时间，`salt` 为随机生成的 16 位十六进制数。
+    pub fn new(q: &'q str, from: &'q str, to: &'q str) -> Self {
+        let curtime = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+                                                  .map(|d| d.as_secs())
+                                                  .unwrap_or_default()
+                                                  .to_string();
+        // 标准库的 RandomState 每次以随机的密钥初始化
+        let salt = format!("{:016x}", RandomState::new().build_hasher().finish());
+        Self { q, from, to, curtime, salt, sign: "".into() }
+    }
+
+    /// 计算 SHA-256 值（v3 签名），返回以表单方式提交的数据。
+    /// 当以下内容至少一项发生变动时，必须调用此方法：
+    /// - User: [appkey]、[key]
+    /// - Query: [q][`Query::q`]、[curtime][`Query::curtime`]、[salt][`Query::salt`]
+    ///
+    /// [appkey]: `User::appkey`
+    /// [key]: `User::key`
+    pub fn sign<'f>(&'f mut self, user: &'f User) -> Form<'f> {
+        let data =
+            format!("{}{}{}{}{}", user.appkey, truncate(self.q), self.salt, self.curtime, user.key);
+        self.sign = format!("{:x}", Sha256::digest(data.as_bytes()));
+        Form::from_user_query(user, self)
+    }
+}
+
+/// 签名所用的 input：q 的字符数大于 20 时，为前 10 个字符 + 字符数 + 后 10 个字符；
+/// 否则为 q 本身。
+pub fn truncate(q: &str) -> Cow<'_, str> {
+    let len = q.chars().count();
+    if len <= 20 {
+        q.into()
+    } else {
+        let head: String = q.chars().take(10).collect();
+        let tail: String = q.chars().skip(len - 10).collect();
+        format!("{head}{len}{tail}").into()
+    }
+}
+
+/// 以表单方式提交的数据
+#[derive(Debug, Serialize)]
+pub struct Form<'f> {
+    pub q:        &'f str,
+    pub from:     &'f str,
+    pub to:       &'f str,
+    #[serde(rename = "appKey")]
+    pub appkey:   &'f str,
+    pub salt:     &'f str,
+    pub sign:     &'f str,
+    #[serde(rename = "signType")]
+    pub signtype: &'f str,
+    pub curtime:  &'f str,
+}
+
+impl<'f> Form<'f> {
+    pub fn from_user_query(user: &'f User, query: &'f Query) -> Self {
+        Self { q:        query.q,
+               from:     query.from,
+               to:       query.to,
+               appkey:   &user.appkey,
+               salt:     &query.salt,
+               sign:     &query.sign,
+               signtype: "v3",
+               curtime:  &query.curtime, }
+    }
+}
+
+/// 响应的信息。要么返回翻译结果，要么返回错误信息。
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Response<'r> {
+    Ok {
+        /// 源语言和目标语言，比如 `en2zh-CHS`
+        #[serde(rename = "l")]
+        lang:        &'r str,
+        /// 译文。原文中的 `\n` 会保留在译文中，需要手动分隔。
+        #[serde(borrow)]
+        translation: Vec<Cow<'r, str>>,
+    },
+    Err(Error),
+}
+
+impl<'r> Response<'r> {
+    /// 提取翻译内容。
+    pub fn dst(&self) -> Result<impl Iterator<Item = &str>, Error> {
+        match self {
+            Response::Ok { translation, .. } => {
+                Ok(translation.iter().flat_map(|s| s.trim_end().split('\n')))
+            }
+            Response::Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// 提取翻译内容。
+    pub fn dst_owned(self) -> Result<Vec<String>, Error> {
+        self.dst().map(|iter| iter.map(String::from).collect())
+    }
+}
+
+/// 错误处理 / 错误码
+#[derive(Debug, Clone, Deserialize)]
+pub struct Error {
+    #[serde(rename = "errorCode")]
+    pub code: String,
+}
+
+impl std::error::Error for Error {}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,
+               "错误码：`{}`\n错误含义：{}\n以上内容由有道智云 API 返回",
+               self.code,
+               self.solution())
+    }
+}
+
+impl Error {
+    /// 参考：[错误码列表](https://ai.youdao.com/DOCSIRMA/html/trans/api/wbfy/index.html)
+    pub fn solution(&self) -> &str {
+        match self.code.as_bytes() {
+            b"101" => "缺少必填的参数。\n解决方法：请检查是否少传参数。",
+            b"102" => "不支持的语言类型。\n解决方法：检查语言是否在语言列表里，简体中文为 zh-CHS。",
+            b"103" => "翻译文本过长。\n解决方法：请减小 limit。",
+            b"104" => "不支持的 API 类型。",
+            b"105" => "不支持的签名类型。",
+            b"106" => "不支持的响应类型。",
+            b"107" => "不支持的传输加密类型。",
+            b"108" => {
+                "应用ID无效。\n解决方法：注册账号，登录后台创建应用和实例并完成绑定，\
+                 可获得应用ID和应用密钥等信息。"
+            }
+            b"109" => "batchLog 格式不正确。",
+            b"110" => {
+                "无相关服务的有效实例。\n解决方法：应用没有绑定服务实例，请新建并绑定服务实例。"
+            }
+            b"111" => "开发者账号无效。",
+            b"112" => "请求服务无效。",
+            b"113" => "q 不能为空。",
+            b"201" => "解密失败。",
+            b"202" => "签名检验失败。\n解决方法：请检查应用ID和应用密钥是否正确。",
+            b"203" => "访问 IP 地址不在可访问 IP 列表。",
+            b"205" => "请求的接口与应用的平台类型不一致。",
+            b"206" => {
+                "因为时间戳无效导致签名校验失败。\n解决方法：请检查本地时间是否和标准时间同步。"
+            }
+            b"207" => "重放请求。",
+            b"301" => "辞典查询失败。",
+            b"302" => "翻译查询失败。",
+            b"303" => "服务端的其它异常。\n解决方法：请重试。",
+            b"304" => "翻译失败。",
+            b"401" => "账户已经欠费。\n解决方法：请进行账户充值。",
+            b"411" => "访问频率受限。\n解决方法：请降低您的调用频率。",
+            b"412" => "长请求过于频繁。\n解决方法：请降低长请求的发送频率。",
+            _ => "未知错误。",
+        }
+    }
+
+    /// 是否为暂时性的错误：服务端异常或者访问频率受限。这些错误可以重试。
+    pub fn is_retriable(&self) -> bool { matches!(self.code.as_bytes(), b"303" | b"411" | b"412") }
+}
+
+#[test]
+fn sign_test() {
+    let user = User { appkey: "id".into(),
+                      key: "key".into(),
+                      ..User::default() };
+    let mut query = Query::new("hello world", "en", "zh-CHS");
+    // 同一秒内的相同请求，salt 也不同
+    assert_ne!(query.salt, Query::new("hello world", "en", "zh-CHS").salt);
+    query.curtime = "1636111645".into();
+    query.salt = "0".into();
+    let form = query.sign(&user);
+    assert_eq!(form.sign, "feda049638ea3255c204573e5288133fbd03b2bef75c7b27d0d592205e101577");
+
+    assert_eq!(truncate("0123456789abcdefghijklmnopqrstuvwxyz"), "012345678936qrstuvwxyz");
+    assert_eq!(truncate("你好，世界"), "你好，世界");
+}
+
+#[test]
+fn response_test() {
+    let success =
+        r#"{"errorCode":"0","query":"hi\nthere","translation":["嗨\n那里"],"l":"en2zh-CHS"}"#;
+    let res: Response = serde_json::from_str(success).unwrap();
+    assert_eq!(res.dst().unwrap().collect::<Vec<_>>(), ["嗨", "那里"]);
+
+    let error = r#"{"errorCode":"202","l":"en2zh-CHS"}"#;
+    let res: Response = serde_json::from_str(error).unwrap();
+    let error = res.dst_owned().unwrap_err();
+    assert_eq!(error.code, "202");
+    assert!(!error.is_retriable());
+}